@@ -0,0 +1,284 @@
+//! Migrations from `#[sqlxinsert(ddl)]` structs
+//!
+//! Building a crate with `SQLXINSERT_METADATA_DIR` set makes `PgInsert` and
+//! `SqliteInsert` write one JSON file per `ddl` struct. Those tables form a
+//! `Snapshot` which is compared with the snapshot stored by the previous run,
+//! the difference is rendered as `alter table` statements.
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+pub mod structs;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Dialect {
+    Postgres,
+    Sqlite,
+}
+
+impl std::str::FromStr for Dialect {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "postgres" => Ok(Dialect::Postgres),
+            "sqlite" => Ok(Dialect::Sqlite),
+            _ => Err(format!("unknown dialect `{}`, use postgres or sqlite", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Column {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub sql_type: String,
+    pub nullable: bool,
+    /// `serial` key skipped by inserts
+    #[serde(default)]
+    pub generated: bool,
+}
+
+/// Table of a `ddl` struct as written by the derive
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Table {
+    pub dialect: Dialect,
+    pub schema: Option<String>,
+    pub table: String,
+    /// `create table` statement of the struct
+    pub create: String,
+    pub columns: Vec<Column>,
+}
+
+impl Table {
+    /// `"billing"."invoice"`
+    pub fn quoted(&self) -> String {
+        match &self.schema {
+            Some(schema) => format!("{}.{}", quote(schema), quote(&self.table)),
+            None => quote(&self.table),
+        }
+    }
+
+    fn key(&self) -> (Dialect, Option<String>, String) {
+        (self.dialect, self.schema.clone(), self.table.clone())
+    }
+
+    fn column(&self, name: &str) -> Option<&Column> {
+        self.columns.iter().find(|column| column.name == name)
+    }
+}
+
+/// All tables, stored as JSON between runs
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub tables: Vec<Table>,
+}
+
+impl Snapshot {
+    /// Read a stored snapshot, empty when `path` does not exist ( first run )
+    pub fn load(path: &Path) -> Result<Self, String> {
+        if !path.exists() {
+            return Ok(Snapshot::default());
+        }
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+        serde_json::from_str(&json)
+            .map_err(|e| format!("invalid snapshot {}: {}", path.display(), e))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(path, json + "\n")
+            .map_err(|e| format!("cannot write {}: {}", path.display(), e))
+    }
+
+    /// Tables of the `*.json` files written by the derive into `dir`
+    pub fn from_metadata_dir(dir: &Path) -> Result<Self, String> {
+        let entries =
+            std::fs::read_dir(dir).map_err(|e| format!("cannot read {}: {}", dir.display(), e))?;
+        let mut tables = Vec::new();
+        for entry in entries {
+            let path = entry.map_err(|e| e.to_string())?.path();
+            if path.extension() == Some("json".as_ref()) {
+                let json = std::fs::read_to_string(&path)
+                    .map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+                let table: Table = serde_json::from_str(&json)
+                    .map_err(|e| format!("invalid metadata {}: {}", path.display(), e))?;
+                tables.push(table);
+            }
+        }
+        Ok(Snapshot::from_tables(tables))
+    }
+
+    /// Tables sorted by dialect, schema and name so stored snapshots diff cleanly
+    pub fn from_tables(tables: Vec<Table>) -> Self {
+        let tables = tables
+            .into_iter()
+            .map(|table| (table.key(), table))
+            .collect::<BTreeMap<_, _>>();
+        Snapshot {
+            tables: tables.into_values().collect(),
+        }
+    }
+
+    /// Only tables of `dialect`
+    pub fn dialect(&self, dialect: Dialect) -> Self {
+        Snapshot {
+            tables: self
+                .tables
+                .iter()
+                .filter(|table| table.dialect == dialect)
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+/// order -> "order"
+fn quote(ident: &str) -> String {
+    format!("\"{}\"", ident.replace('"', "\"\""))
+}
+
+/// Type usable in `alter column ... type` and `add column`: `serial` stays, but is `integer` when altered
+fn altered_type(sql_type: &str) -> &str {
+    match sql_type {
+        "smallserial" => "smallint",
+        "serial" => "integer",
+        "bigserial" => "bigint",
+        other => other,
+    }
+}
+
+/// Statements migrating tables of `previous` to `current`, each ending with `;`
+///
+/// Renamed columns and tables are dropped and added again, review before applying.
+/// SQLite cannot alter column types or nullability, those are left as comments.
+pub fn migration(previous: &Snapshot, current: &Snapshot) -> Vec<String> {
+    let mut statements = Vec::new();
+    for table in &current.tables {
+        let old = match previous.tables.iter().find(|old| old.key() == table.key()) {
+            Some(old) => old,
+            None => {
+                statements.push(format!("{};", table.create));
+                continue;
+            }
+        };
+        let name = table.quoted();
+        for column in &table.columns {
+            let old_column = match old.column(&column.name) {
+                Some(old_column) => old_column,
+                None => {
+                    statements.extend(add_column(table, column));
+                    continue;
+                }
+            };
+            if altered_type(&old_column.sql_type) != altered_type(&column.sql_type) {
+                statements.push(match table.dialect {
+                    Dialect::Postgres => format!(
+                        "alter table {} alter column {} type {} using {}::{};",
+                        name,
+                        quote(&column.name),
+                        altered_type(&column.sql_type),
+                        quote(&column.name),
+                        altered_type(&column.sql_type)
+                    ),
+                    Dialect::Sqlite => format!(
+                        "-- SQLite cannot alter column {} of {} from {} to {}, rebuild the table",
+                        quote(&column.name),
+                        name,
+                        old_column.sql_type,
+                        column.sql_type
+                    ),
+                });
+            }
+            if old_column.nullable != column.nullable && !column.generated {
+                let change = if column.nullable { "drop" } else { "set" };
+                statements.push(match table.dialect {
+                    Dialect::Postgres => format!(
+                        "alter table {} alter column {} {} not null;",
+                        name,
+                        quote(&column.name),
+                        change
+                    ),
+                    Dialect::Sqlite => format!(
+                        "-- SQLite cannot {} not null of column {} of {}, rebuild the table",
+                        change,
+                        quote(&column.name),
+                        name
+                    ),
+                });
+            }
+        }
+        for old_column in &old.columns {
+            if table.column(&old_column.name).is_none() {
+                statements.push(format!(
+                    "alter table {} drop column {};",
+                    name,
+                    quote(&old_column.name)
+                ));
+            }
+        }
+    }
+    for old in &previous.tables {
+        if !current.tables.iter().any(|table| table.key() == old.key()) {
+            statements.push(format!("drop table {};", old.quoted()));
+        }
+    }
+    statements
+}
+
+fn add_column(table: &Table, column: &Column) -> Vec<String> {
+    let add = format!(
+        "alter table {} add column {} {}",
+        table.quoted(),
+        quote(&column.name),
+        column.sql_type
+    );
+    match (table.dialect, column.nullable || column.generated) {
+        (_, true) => vec![format!("{};", add)],
+        (Dialect::Postgres, false) => vec![
+            format!(
+                "-- {} needs a default or backfill when the table has rows",
+                quote(&column.name)
+            ),
+            format!("{} not null;", add),
+        ],
+        // SQLite rejects `not null` columns without a default
+        (Dialect::Sqlite, false) => vec![
+            format!(
+                "-- {} is not null in the struct, SQLite cannot add it without a default",
+                quote(&column.name)
+            ),
+            format!("{};", add),
+        ],
+    }
+}
+
+/// `20261019120000_add_seats.sql`, the file name `sqlx migrate` expects
+pub fn migration_file_name(version: &str, name: &str) -> String {
+    let description = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+    format!("{}_{}.sql", version, description)
+}
+
+/// Migration file content, `None` when nothing changed
+pub fn render_migration(previous: &Snapshot, current: &Snapshot) -> Option<String> {
+    let statements = migration(previous, current);
+    if statements.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "-- Generated by sqlxinsert-cli\n{}\n",
+        statements.join("\n")
+    ))
+}
@@ -0,0 +1,193 @@
+//! `sqlxinsert-cli migrate <name>` writes a migration for the `ddl` structs which
+//! changed since the stored snapshot, `sqlxinsert-cli snapshot` only stores it.
+//! `sqlxinsert-cli structs <file>` prints structs of a SQLite database or `pg_dump` file.
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use sqlxinsert_cli::{migration_file_name, render_migration, structs, Dialect, Snapshot};
+
+const USAGE: &str = "\
+Usage:
+    sqlxinsert-cli migrate <name> [options]
+    sqlxinsert-cli snapshot [options]
+    sqlxinsert-cli structs <sqlite database or pg_dump --schema-only file> [--output <file>]
+
+Options:
+    --dialect <postgres|sqlite>  tables to compare [default: postgres]
+    --snapshot <file>            stored snapshot [default: sqlxinsert.json]
+    --migrations <dir>           migrations directory [default: migrations]
+    --metadata <dir>             read metadata written by a previous build instead of running cargo check
+    --output <file>              write structs to a file instead of stdout
+";
+
+struct Args {
+    command: String,
+    name: Option<String>,
+    dialect: Dialect,
+    snapshot: PathBuf,
+    migrations: PathBuf,
+    metadata: Option<PathBuf>,
+    output: Option<PathBuf>,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let command = args.next().ok_or("missing command")?;
+    let mut parsed = Args {
+        command,
+        name: None,
+        dialect: Dialect::Postgres,
+        snapshot: PathBuf::from("sqlxinsert.json"),
+        migrations: PathBuf::from("migrations"),
+        metadata: None,
+        output: None,
+    };
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("missing value of {}", arg));
+        match arg.as_str() {
+            "--dialect" => parsed.dialect = value()?.parse()?,
+            "--snapshot" => parsed.snapshot = value()?.into(),
+            "--migrations" => parsed.migrations = value()?.into(),
+            "--metadata" => parsed.metadata = Some(value()?.into()),
+            "--output" => parsed.output = Some(value()?.into()),
+            _ if arg.starts_with("--") => return Err(format!("unknown option {}", arg)),
+            _ if parsed.name.is_none() => parsed.name = Some(arg),
+            _ => return Err(format!("unexpected argument {}", arg)),
+        }
+    }
+    Ok(parsed)
+}
+
+fn cargo(args: &[&str]) -> Command {
+    let mut command = Command::new(std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string()));
+    command.args(args);
+    command
+}
+
+/// Names of the workspace packages
+fn workspace_packages() -> Result<Vec<String>, String> {
+    let output = cargo(&["metadata", "--no-deps", "--format-version", "1"])
+        .output()
+        .map_err(|e| format!("cannot run cargo metadata: {}", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).into_owned());
+    }
+    let metadata: serde_json::Value =
+        serde_json::from_slice(&output.stdout).map_err(|e| e.to_string())?;
+    Ok(metadata["packages"]
+        .as_array()
+        .map(|packages| {
+            packages
+                .iter()
+                .filter_map(|package| package["name"].as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// Check the workspace with `SQLXINSERT_METADATA_DIR` set, workspace packages are cleaned
+/// first ( in a separate target directory ) so every derive runs again
+fn build_metadata() -> Result<PathBuf, String> {
+    let target = Path::new("target").join("sqlxinsert");
+    let metadata = std::env::current_dir()
+        .map_err(|e| e.to_string())?
+        .join(&target)
+        .join("metadata");
+    if metadata.exists() {
+        std::fs::remove_dir_all(&metadata).map_err(|e| e.to_string())?;
+    }
+    let target = target.to_string_lossy().into_owned();
+    let mut clean = cargo(&["clean", "--target-dir", &target]);
+    for package in workspace_packages()? {
+        clean.args(["-p", &package]);
+    }
+    let status = clean.status().map_err(|e| e.to_string())?;
+    if !status.success() {
+        return Err("cargo clean failed".to_string());
+    }
+    // lib and bin targets only, `ddl` structs of tests and examples are not migrated
+    let status = cargo(&["check", "--workspace", "--target-dir", &target])
+        .env("SQLXINSERT_METADATA_DIR", &metadata)
+        .status()
+        .map_err(|e| e.to_string())?;
+    if !status.success() {
+        return Err("cargo check failed".to_string());
+    }
+    if !metadata.exists() {
+        return Err("no #[sqlxinsert(ddl)] structs found".to_string());
+    }
+    Ok(metadata)
+}
+
+/// Structs of the tables in `path`, SQLite databases are recognized by their header
+fn generate_structs(path: &Path) -> Result<String, String> {
+    let content =
+        std::fs::read(path).map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+    if content.starts_with(b"SQLite format 3\0") {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| e.to_string())?;
+        runtime.block_on(structs::from_sqlite(&path.to_string_lossy()))
+    } else {
+        let sql = String::from_utf8(content)
+            .map_err(|_| format!("{} is neither SQL nor a SQLite database", path.display()))?;
+        Ok(structs::from_sql(&sql, Dialect::Postgres))
+    }
+}
+
+fn run(args: Args) -> Result<(), String> {
+    if args.command == "structs" {
+        let path = args.name.as_deref().ok_or("missing schema file")?;
+        let source = generate_structs(Path::new(path))?;
+        match &args.output {
+            Some(output) => {
+                std::fs::write(output, source)
+                    .map_err(|e| format!("cannot write {}: {}", output.display(), e))?;
+                println!("Created {}", output.display());
+            }
+            None => print!("{}", source),
+        }
+        return Ok(());
+    }
+    let metadata = match &args.metadata {
+        Some(metadata) => metadata.clone(),
+        None => build_metadata()?,
+    };
+    let current = Snapshot::from_metadata_dir(&metadata)?.dialect(args.dialect);
+    let stored = Snapshot::load(&args.snapshot)?;
+    let previous = stored.dialect(args.dialect);
+    match args.command.as_str() {
+        "migrate" => {
+            let name = args.name.as_deref().ok_or("missing migration name")?;
+            match render_migration(&previous, &current) {
+                Some(migration) => {
+                    let version = chrono::Utc::now().format("%Y%m%d%H%M%S").to_string();
+                    std::fs::create_dir_all(&args.migrations).map_err(|e| e.to_string())?;
+                    let path = args.migrations.join(migration_file_name(&version, name));
+                    std::fs::write(&path, migration)
+                        .map_err(|e| format!("cannot write {}: {}", path.display(), e))?;
+                    println!("Created {}", path.display());
+                }
+                None => println!("No changes"),
+            }
+        }
+        "snapshot" => {}
+        command => return Err(format!("unknown command {}", command)),
+    }
+    // tables of other dialects stay as they were
+    let others = stored
+        .tables
+        .into_iter()
+        .filter(|table| table.dialect != args.dialect);
+    Snapshot::from_tables(others.chain(current.tables).collect()).save(&args.snapshot)?;
+    println!("Saved {}", args.snapshot.display());
+    Ok(())
+}
+
+fn main() {
+    let result = parse_args(std::env::args().skip(1)).and_then(run);
+    if let Err(e) = result {
+        eprintln!("error: {}\n\n{}", e, USAGE);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,337 @@
+//! Rust structs from an existing schema: a `pg_dump --schema-only` file or a SQLite database
+//!
+//! Every table becomes a `PgInsert` struct, `insert(skip)` lists the columns the database
+//! fills in ( `serial`, identity, generated ), `update(by)` the primary key and `conflict`
+//! the first unique constraint. Postgres enums become `sqlx::Type` enums.
+use heck::ToUpperCamelCase;
+use sqlxinsert_schema::{canonical_type, Enum, Schema, Table};
+
+use crate::Dialect;
+
+/// Source of all tables in `sql` ( statements of a dump or `sqlite_master` )
+pub fn from_sql(sql: &str, dialect: Dialect) -> String {
+    let mut schema = Schema::default();
+    schema.apply(sql);
+    let items = schema
+        .enums
+        .iter()
+        .map(enum_type)
+        .chain(
+            schema
+                .tables
+                .iter()
+                .map(|table| table_struct(table, &schema.enums, dialect)),
+        )
+        .collect::<Vec<_>>();
+    format!(
+        "// Generated by sqlxinsert-cli, `PgInsert` implements the `DBOps` trait in scope\n// review keys and types before use\n\n{}",
+        items.join("\n")
+    )
+}
+
+/// Source of all tables of the SQLite database at `path`
+pub async fn from_sqlite(path: &str) -> Result<String, String> {
+    use sqlx::Connection;
+
+    let url = format!("sqlite://{}?mode=ro", path);
+    let mut conn = sqlx::SqliteConnection::connect(&url)
+        .await
+        .map_err(|e| format!("cannot open {}: {}", path, e))?;
+    // tables before the indexes on them
+    let rows: Vec<(String,)> = sqlx::query_as(
+        "select sql from sqlite_master where type in ('table', 'index') and sql is not null \
+         and name not like 'sqlite_%' order by type = 'index', rowid",
+    )
+    .fetch_all(&mut conn)
+    .await
+    .map_err(|e| e.to_string())?;
+    let sql = rows
+        .into_iter()
+        .map(|(sql,)| sql)
+        .collect::<Vec<_>>()
+        .join(";\n");
+    Ok(from_sql(&sql, Dialect::Sqlite))
+}
+
+/// cars -> Car, car_owners -> CarOwner, categories -> Category
+fn struct_name(table: &str) -> String {
+    let singular = if let Some(stem) = table.strip_suffix("ies") {
+        format!("{}y", stem)
+    } else if table.ends_with("sses") {
+        table[..table.len() - 2].to_string()
+    } else if table.ends_with('s') && !table.ends_with("ss") && !table.ends_with("us") {
+        table[..table.len() - 1].to_string()
+    } else {
+        table.to_string()
+    };
+    singular.to_upper_camel_case()
+}
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "static", "struct", "trait", "true", "type", "unsafe", "use", "where",
+    "while",
+];
+
+/// Field of a column: `type` -> `r#type` ( `PgInsert` maps it back to `type` )
+fn field_name(column: &str) -> Result<String, String> {
+    let valid = column
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_lowercase() || c == '_')
+        && column
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if !valid {
+        return Err(format!(
+            "column \"{}\" is not a snake case field name",
+            column
+        ));
+    }
+    if KEYWORDS.contains(&column) {
+        Ok(format!("r#{}", column))
+    } else {
+        Ok(column.to_string())
+    }
+}
+
+/// Rust type of a column type and the `pg_type` of user defined types, bound and decoded as
+/// the generated enum or `String`
+///
+/// ```rust
+/// use sqlxinsert_cli::{structs::rust_type, Dialect};
+///
+/// let postgres = |sql_type| rust_type(sql_type, &[], Dialect::Postgres);
+/// assert_eq!(postgres("character varying(40)"), ("String".to_string(), None));
+/// assert_eq!(postgres("int4").0, "i32");
+/// assert_eq!(postgres("numeric(10,2)").0, "sqlx::types::BigDecimal");
+/// assert_eq!(
+///     postgres("public.car_size[]"),
+///     ("Vec<String>".to_string(), Some("car_size".to_string()))
+/// );
+/// assert_eq!(rust_type("INTEGER", &[], Dialect::Sqlite).0, "i64");
+/// assert_eq!(rust_type("varchar(20)", &[], Dialect::Sqlite).0, "String");
+/// ```
+pub fn rust_type(sql_type: &str, enums: &[Enum], dialect: Dialect) -> (String, Option<String>) {
+    match dialect {
+        Dialect::Postgres => postgres_type(sql_type, enums),
+        Dialect::Sqlite => (sqlite_type(sql_type).to_string(), None),
+    }
+}
+
+fn postgres_type(sql_type: &str, enums: &[Enum]) -> (String, Option<String>) {
+    let canonical = canonical_type(sql_type);
+    if let Some(element) = canonical.strip_suffix("[]") {
+        let (inner, pg_type) = postgres_type(element, enums);
+        return (format!("Vec<{}>", inner), pg_type);
+    }
+    let rust = match canonical.as_str() {
+        "boolean" => "bool",
+        "smallint" => "i16",
+        "integer" => "i32",
+        "bigint" => "i64",
+        "real" => "f32",
+        "double precision" => "f64",
+        "text" => "String",
+        "bytea" => "Vec<u8>",
+        "timestamptz" => "sqlx::types::chrono::DateTime<sqlx::types::chrono::Utc>",
+        "timestamp" => "sqlx::types::chrono::NaiveDateTime",
+        "date" => "sqlx::types::chrono::NaiveDate",
+        "time" => "sqlx::types::chrono::NaiveTime",
+        "numeric" => "sqlx::types::BigDecimal",
+        "json" | "jsonb" => "sqlx::types::JsonValue",
+        "uuid" => "sqlx::types::Uuid",
+        "inet" | "cidr" => "sqlx::types::ipnetwork::IpNetwork",
+        other => {
+            let pg_type = other.strip_prefix("public.").unwrap_or(other);
+            let (schema, name) = match pg_type.split_once('.') {
+                Some((schema, name)) => (Some(schema), name),
+                None => (None, pg_type),
+            };
+            let enum_type = enums.iter().find(|e| {
+                e.name == name && e.schema.as_deref().filter(|s| *s != "public") == schema
+            });
+            return match enum_type {
+                Some(enum_type) => (enum_name(enum_type), Some(pg_type.to_string())),
+                None => ("String".to_string(), Some(pg_type.to_string())),
+            };
+        }
+    };
+    (rust.to_string(), None)
+}
+
+/// SQLite type affinity rules
+fn sqlite_type(sql_type: &str) -> &'static str {
+    let sql_type = sql_type.to_lowercase();
+    if sql_type.contains("bool") {
+        "bool"
+    } else if sql_type.contains("int") {
+        "i64"
+    } else if sql_type.contains("char") || sql_type.contains("clob") || sql_type.contains("text") {
+        "String"
+    } else if sql_type.contains("blob") || sql_type.is_empty() {
+        "Vec<u8>"
+    } else if sql_type.contains("real") || sql_type.contains("floa") || sql_type.contains("doub") {
+        "f64"
+    } else if sql_type.contains("date") || sql_type.contains("time") {
+        "String"
+    } else {
+        "f64"
+    }
+}
+
+/// car_color -> CarColor
+fn enum_name(enum_type: &Enum) -> String {
+    enum_type.name.to_upper_camel_case()
+}
+
+/// `sqlx::Type` enum with one variant per value, `Vec` fields need `PgHasArrayType`
+fn enum_type(enum_type: &Enum) -> String {
+    let name = enum_name(enum_type);
+    let type_name = match enum_type
+        .schema
+        .as_deref()
+        .filter(|schema| *schema != "public")
+    {
+        Some(schema) => format!("{}.{}", schema, enum_type.name),
+        None => enum_type.name.clone(),
+    };
+    let variants = enum_type
+        .values
+        .iter()
+        .map(|value| {
+            let variant = match value.to_upper_camel_case() {
+                variant if variant.is_empty() => "Empty".to_string(),
+                variant if variant.starts_with(|c: char| c.is_ascii_digit()) => {
+                    format!("V{}", variant)
+                }
+                variant => variant,
+            };
+            format!("    #[sqlx(rename = {:?})]\n    {},", value, variant)
+        })
+        .collect::<Vec<_>>();
+    format!(
+        "/// enum {type_name}\n\
+         #[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type)]\n\
+         #[sqlx(type_name = \"{type_name}\")]\n\
+         pub enum {name} {{\n{variants}\n}}\n\n\
+         impl sqlx::postgres::PgHasArrayType for {name} {{\n    \
+             fn array_type_info() -> sqlx::postgres::PgTypeInfo {{\n        \
+                 sqlx::postgres::PgTypeInfo::with_name(\"_{array}\")\n    \
+             }}\n\
+         }}\n",
+        type_name = type_name,
+        name = name,
+        variants = variants.join("\n"),
+        array = enum_type.name,
+    )
+}
+
+fn table_struct(table: &Table, enums: &[Enum], dialect: Dialect) -> String {
+    let mut generated = table
+        .columns
+        .iter()
+        .filter(|column| column.generated)
+        .map(|column| column.name.clone())
+        .collect::<Vec<_>>();
+    // SQLite `integer primary key` is an alias of the rowid
+    if let (Dialect::Sqlite, [key]) = (dialect, table.primary_key.as_slice()) {
+        let is_integer = table
+            .column(key)
+            .is_some_and(|column| column.sql_type.eq_ignore_ascii_case("integer"));
+        if is_integer && !generated.contains(key) {
+            generated.push(key.clone());
+        }
+    }
+
+    let mut comments = Vec::new();
+    let key = if !table.primary_key.is_empty() {
+        table.primary_key.clone()
+    } else if let Some(unique) = table.unique.first() {
+        unique.clone()
+    } else {
+        comments.push(
+            "no primary key or unique constraint, update(by) uses the first column".to_string(),
+        );
+        table
+            .columns
+            .iter()
+            .take(1)
+            .map(|column| column.name.clone())
+            .collect()
+    };
+    let update_skip = generated
+        .iter()
+        .filter(|column| !key.contains(column))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    let mut attrs = vec![format!("table = \"{}\"", table.name)];
+    if let Some(schema) = table.schema.as_deref().filter(|schema| *schema != "public") {
+        attrs.push(format!("schema = \"{}\"", schema));
+    }
+    attrs.push(format!("insert(skip = \"{}\")", generated.join(", ")));
+    if update_skip.is_empty() {
+        attrs.push(format!("update(by = \"{}\")", key.join(", ")));
+    } else {
+        attrs.push(format!(
+            "update(by = \"{}\", skip = \"{}\")",
+            key.join(", "),
+            update_skip.join(", ")
+        ));
+    }
+    if let Some(conflict) = table
+        .unique
+        .first()
+        .or_else(|| Some(&table.primary_key).filter(|key| !key.is_empty()))
+    {
+        attrs.push(format!("conflict = \"{}\"", conflict.join(", ")));
+    }
+
+    let mut fields = Vec::new();
+    for column in &table.columns {
+        let name = match field_name(&column.name) {
+            Ok(name) => name,
+            Err(e) => {
+                comments.push(e);
+                continue;
+            }
+        };
+        let (ty, pg_type) = rust_type(&column.sql_type, enums, dialect);
+        let as_string = ty.ends_with("String") || ty.ends_with("String>");
+        let nullable = !column.not_null && !table.primary_key.contains(&column.name);
+        let ty = if nullable {
+            format!("Option<{}>", ty)
+        } else {
+            ty
+        };
+        if let Some(pg_type) = pg_type {
+            if as_string {
+                comments.push(format!(
+                    "`{}` is a user defined type, bound and decoded as String",
+                    column.name
+                ));
+            }
+            fields.push(format!("    #[sqlxinsert(pg_type = \"{}\")]", pg_type));
+        }
+        fields.push(format!("    pub {}: {},", name, ty));
+    }
+
+    let quoted = match &table.schema {
+        Some(schema) => format!("\"{}\".\"{}\"", schema, table.name),
+        None => format!("\"{}\"", table.name),
+    };
+    let mut source = format!("/// {}\n", quoted);
+    for comment in comments {
+        source.push_str(&format!("// TODO: {}\n", comment));
+    }
+    source.push_str("#[derive(Debug, Clone, sqlx::FromRow, sqlxinsert::PgInsert)]\n");
+    source.push_str(&format!("#[sqlxinsert({})]\n", attrs.join(", ")));
+    source.push_str(&format!(
+        "pub struct {} {{\n{}\n}}\n",
+        struct_name(&table.name),
+        fields.join("\n")
+    ));
+    source
+}
@@ -0,0 +1,200 @@
+--
+-- PostgreSQL database dump
+--
+
+\restrict p9SHaf0DILbbHdi0Vp0NZPRDEZa8RYYYjwqVKL7tlbkFHJP5yiqHnTYdlVS1HMq
+
+-- Dumped from database version 15.18 (Debian 15.18-0+deb12u1)
+-- Dumped by pg_dump version 15.18 (Debian 15.18-0+deb12u1)
+
+SET statement_timeout = 0;
+SET lock_timeout = 0;
+SET idle_in_transaction_session_timeout = 0;
+SET client_encoding = 'SQL_ASCII';
+SET standard_conforming_strings = on;
+SELECT pg_catalog.set_config('search_path', '', false);
+SET check_function_bodies = false;
+SET xmloption = content;
+SET client_min_messages = warning;
+SET row_security = off;
+
+--
+-- Name: billing; Type: SCHEMA; Schema: -; Owner: -
+--
+
+CREATE SCHEMA billing;
+
+
+--
+-- Name: car_color; Type: TYPE; Schema: public; Owner: -
+--
+
+CREATE TYPE public.car_color AS ENUM (
+    'red',
+    'blue'
+);
+
+
+--
+-- Name: touch(); Type: FUNCTION; Schema: public; Owner: -
+--
+
+CREATE FUNCTION public.touch() RETURNS trigger
+    LANGUAGE plpgsql
+    AS $$
+BEGIN
+    NEW.updated_at = now(); -- create table nope ( x int );
+    RETURN NEW;
+END;
+$$;
+
+
+SET default_tablespace = '';
+
+SET default_table_access_method = heap;
+
+--
+-- Name: invoices; Type: TABLE; Schema: billing; Owner: -
+--
+
+CREATE TABLE billing.invoices (
+    id bigint NOT NULL,
+    number text NOT NULL,
+    car_id integer,
+    amount double precision NOT NULL,
+    amount_with_tax double precision GENERATED ALWAYS AS ((amount * (1.2)::double precision)) STORED,
+    issued_on date NOT NULL
+);
+
+
+--
+-- Name: invoices_id_seq; Type: SEQUENCE; Schema: billing; Owner: -
+--
+
+ALTER TABLE billing.invoices ALTER COLUMN id ADD GENERATED ALWAYS AS IDENTITY (
+    SEQUENCE NAME billing.invoices_id_seq
+    START WITH 1
+    INCREMENT BY 1
+    NO MINVALUE
+    NO MAXVALUE
+    CACHE 1
+);
+
+
+--
+-- Name: audit_log; Type: TABLE; Schema: public; Owner: -
+--
+
+CREATE TABLE public.audit_log (
+    message text NOT NULL,
+    created_at timestamp with time zone DEFAULT now() NOT NULL
+);
+
+
+--
+-- Name: car_owners; Type: TABLE; Schema: public; Owner: -
+--
+
+CREATE TABLE public.car_owners (
+    car_id integer NOT NULL,
+    owner_name text NOT NULL,
+    since timestamp without time zone
+);
+
+
+--
+-- Name: cars; Type: TABLE; Schema: public; Owner: -
+--
+
+CREATE TABLE public.cars (
+    id integer NOT NULL,
+    name character varying(40) NOT NULL,
+    color public.car_color,
+    type text DEFAULT 'sedan'::text NOT NULL,
+    tags text[] DEFAULT '{}'::text[] NOT NULL,
+    spec jsonb,
+    electric boolean DEFAULT false NOT NULL,
+    updated_at timestamp with time zone DEFAULT now() NOT NULL
+);
+
+
+--
+-- Name: cars_id_seq; Type: SEQUENCE; Schema: public; Owner: -
+--
+
+CREATE SEQUENCE public.cars_id_seq
+    AS integer
+    START WITH 1
+    INCREMENT BY 1
+    NO MINVALUE
+    NO MAXVALUE
+    CACHE 1;
+
+
+--
+-- Name: cars_id_seq; Type: SEQUENCE OWNED BY; Schema: public; Owner: -
+--
+
+ALTER SEQUENCE public.cars_id_seq OWNED BY public.cars.id;
+
+
+--
+-- Name: cars id; Type: DEFAULT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.cars ALTER COLUMN id SET DEFAULT nextval('public.cars_id_seq'::regclass);
+
+
+--
+-- Name: invoices invoices_pkey; Type: CONSTRAINT; Schema: billing; Owner: -
+--
+
+ALTER TABLE ONLY billing.invoices
+    ADD CONSTRAINT invoices_pkey PRIMARY KEY (id);
+
+
+--
+-- Name: car_owners car_owners_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.car_owners
+    ADD CONSTRAINT car_owners_pkey PRIMARY KEY (car_id, owner_name);
+
+
+--
+-- Name: cars cars_name_key; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.cars
+    ADD CONSTRAINT cars_name_key UNIQUE (name);
+
+
+--
+-- Name: cars cars_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.cars
+    ADD CONSTRAINT cars_pkey PRIMARY KEY (id);
+
+
+--
+-- Name: invoices_number; Type: INDEX; Schema: billing; Owner: -
+--
+
+CREATE UNIQUE INDEX invoices_number ON billing.invoices USING btree (number);
+
+
+--
+-- Name: invoices invoices_car_id_fkey; Type: FK CONSTRAINT; Schema: billing; Owner: -
+--
+
+ALTER TABLE ONLY billing.invoices
+    ADD CONSTRAINT invoices_car_id_fkey FOREIGN KEY (car_id) REFERENCES public.cars(id);
+
+
+--
+-- PostgreSQL database dump complete
+--
+
+\unrestrict p9SHaf0DILbbHdi0Vp0NZPRDEZa8RYYYjwqVKL7tlbkFHJP5yiqHnTYdlVS1HMq
+
@@ -0,0 +1,21 @@
+CREATE TABLE cars (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name VARCHAR(40) NOT NULL UNIQUE,
+    wheels SMALLINT NOT NULL DEFAULT 4,
+    price REAL,
+    electric BOOLEAN NOT NULL DEFAULT 0,
+    photo BLOB,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE TABLE car_owners (
+    car_id INTEGER NOT NULL REFERENCES cars ( id ),
+    owner_name TEXT NOT NULL,
+    since TEXT,
+    PRIMARY KEY ( car_id, owner_name )
+);
+CREATE TABLE categories (
+    code TEXT NOT NULL,
+    label TEXT,
+    "Sort Order" INTEGER
+);
+CREATE UNIQUE INDEX categories_code ON categories ( code );
@@ -0,0 +1,68 @@
+//! Golden structs of `structs_test` compile with `PgInsert` and generate the expected statements
+use async_trait::async_trait;
+
+// only compiled, nothing is run against a database
+#[allow(dead_code)]
+#[async_trait]
+trait DBOps: Send + Unpin + Sized {
+    const TABLE_NAME: &'static str;
+
+    async fn insert<'e, E>(&self, pool: E) -> eyre::Result<Self>
+    where
+        E: sqlx::Executor<'e, Database = sqlx::Postgres>;
+
+    async fn update<'e, E>(&self, pool: E) -> eyre::Result<Option<Self>>
+    where
+        E: sqlx::Executor<'e, Database = sqlx::Postgres>;
+
+    async fn upsert<'e, E>(&self, pool: E) -> eyre::Result<Self>
+    where
+        E: sqlx::Executor<'e, Database = sqlx::Postgres>;
+}
+
+#[allow(dead_code)]
+mod postgres {
+    use super::DBOps;
+
+    include!("golden/pg_dump_structs.rs");
+}
+
+#[allow(dead_code)]
+mod sqlite {
+    use super::DBOps;
+
+    include!("golden/sqlite_structs.rs");
+}
+
+#[test]
+fn test_pg_dump_structs() {
+    use postgres::*;
+
+    assert_eq!(
+        Car::insert_sql(&Car::TABLE),
+        r#"insert into "cars" ( "name", "color", "type", "tags", "spec", "electric", "updated_at" ) values ( $1,$2::car_color,$3,$4,$5,$6,$7 ) returning *"#
+    );
+    assert_eq!(
+        Invoice::upsert_sql(&Invoice::TABLE),
+        r#"insert into "billing"."invoices" ( "number", "car_id", "amount", "issued_on" ) values ( $1,$2,$3,$4 ) on conflict ("number") do update set "number"=$5,"car_id"=$6,"amount"=$7,"issued_on"=$8 returning *"#
+    );
+    assert_eq!(
+        CarOwner::update_sql(&CarOwner::TABLE),
+        r#"update "car_owners" set "since"=$1 where "car_id"=$2 AND "owner_name"=$3 returning *"#
+    );
+    assert_eq!(AuditLog::TABLE_NAME, "audit_log");
+}
+
+#[test]
+fn test_sqlite_structs() {
+    use sqlite::*;
+
+    assert_eq!(
+        Car::insert_sql(&Car::TABLE),
+        r#"insert into "cars" ( "name", "wheels", "price", "electric", "photo", "created_at" ) values ( $1,$2,$3,$4,$5,$6 ) returning *"#
+    );
+    assert_eq!(
+        Category::update_sql(&Category::TABLE),
+        r#"update "categories" set "label"=$1 where "code"=$2 returning *"#
+    );
+}
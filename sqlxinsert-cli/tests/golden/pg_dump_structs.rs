@@ -0,0 +1,63 @@
+// Generated by sqlxinsert-cli, `PgInsert` implements the `DBOps` trait in scope
+// review keys and types before use
+
+/// enum car_color
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "car_color")]
+pub enum CarColor {
+    #[sqlx(rename = "red")]
+    Red,
+    #[sqlx(rename = "blue")]
+    Blue,
+}
+
+impl sqlx::postgres::PgHasArrayType for CarColor {
+    fn array_type_info() -> sqlx::postgres::PgTypeInfo {
+        sqlx::postgres::PgTypeInfo::with_name("_car_color")
+    }
+}
+
+/// "billing"."invoices"
+#[derive(Debug, Clone, sqlx::FromRow, sqlxinsert::PgInsert)]
+#[sqlxinsert(table = "invoices", schema = "billing", insert(skip = "id, amount_with_tax"), update(by = "id", skip = "amount_with_tax"), conflict = "number")]
+pub struct Invoice {
+    pub id: i64,
+    pub number: String,
+    pub car_id: Option<i32>,
+    pub amount: f64,
+    pub amount_with_tax: Option<f64>,
+    pub issued_on: sqlx::types::chrono::NaiveDate,
+}
+
+/// "public"."audit_log"
+// TODO: no primary key or unique constraint, update(by) uses the first column
+#[derive(Debug, Clone, sqlx::FromRow, sqlxinsert::PgInsert)]
+#[sqlxinsert(table = "audit_log", insert(skip = ""), update(by = "message"))]
+pub struct AuditLog {
+    pub message: String,
+    pub created_at: sqlx::types::chrono::DateTime<sqlx::types::chrono::Utc>,
+}
+
+/// "public"."car_owners"
+#[derive(Debug, Clone, sqlx::FromRow, sqlxinsert::PgInsert)]
+#[sqlxinsert(table = "car_owners", insert(skip = ""), update(by = "car_id, owner_name"), conflict = "car_id, owner_name")]
+pub struct CarOwner {
+    pub car_id: i32,
+    pub owner_name: String,
+    pub since: Option<sqlx::types::chrono::NaiveDateTime>,
+}
+
+/// "public"."cars"
+#[derive(Debug, Clone, sqlx::FromRow, sqlxinsert::PgInsert)]
+#[sqlxinsert(table = "cars", insert(skip = "id"), update(by = "id"), conflict = "name")]
+pub struct Car {
+    pub id: i32,
+    pub name: String,
+    #[sqlxinsert(pg_type = "car_color")]
+    pub color: Option<CarColor>,
+    pub r#type: String,
+    pub tags: Vec<String>,
+    pub spec: Option<sqlx::types::JsonValue>,
+    pub electric: bool,
+    pub updated_at: sqlx::types::chrono::DateTime<sqlx::types::chrono::Utc>,
+}
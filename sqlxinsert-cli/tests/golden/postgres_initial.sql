@@ -0,0 +1,3 @@
+-- Generated by sqlxinsert-cli
+create table "cars" ( "id" serial, "name" text not null, "color" text, "wheels" smallint not null, primary key ("id"), unique ("name") );
+create table "billing"."invoice" ( "id" bigserial, "amount" double precision not null, primary key ("id") );
@@ -0,0 +1,10 @@
+-- Generated by sqlxinsert-cli
+alter table "cars" alter column "id" type bigint using "id"::bigint;
+alter table "cars" alter column "name" type varchar(40) using "name"::varchar(40);
+alter table "cars" alter column "color" set not null;
+-- "seats" needs a default or backfill when the table has rows
+alter table "cars" add column "seats" integer not null;
+alter table "cars" add column "nickname" text;
+alter table "cars" drop column "wheels";
+create table "owners" ( "car_id" bigint not null, "owner" text not null, primary key ("car_id", "owner") );
+drop table "billing"."invoice";
@@ -0,0 +1,33 @@
+// Generated by sqlxinsert-cli, `PgInsert` implements the `DBOps` trait in scope
+// review keys and types before use
+
+/// "cars"
+#[derive(Debug, Clone, sqlx::FromRow, sqlxinsert::PgInsert)]
+#[sqlxinsert(table = "cars", insert(skip = "id"), update(by = "id"), conflict = "name")]
+pub struct Car {
+    pub id: i64,
+    pub name: String,
+    pub wheels: i64,
+    pub price: Option<f64>,
+    pub electric: bool,
+    pub photo: Option<Vec<u8>>,
+    pub created_at: String,
+}
+
+/// "car_owners"
+#[derive(Debug, Clone, sqlx::FromRow, sqlxinsert::PgInsert)]
+#[sqlxinsert(table = "car_owners", insert(skip = ""), update(by = "car_id, owner_name"), conflict = "car_id, owner_name")]
+pub struct CarOwner {
+    pub car_id: i64,
+    pub owner_name: String,
+    pub since: Option<String>,
+}
+
+/// "categories"
+// TODO: column "Sort Order" is not a snake case field name
+#[derive(Debug, Clone, sqlx::FromRow, sqlxinsert::PgInsert)]
+#[sqlxinsert(table = "categories", insert(skip = ""), update(by = "code"), conflict = "code")]
+pub struct Category {
+    pub code: String,
+    pub label: Option<String>,
+}
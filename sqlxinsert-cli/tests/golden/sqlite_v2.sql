@@ -0,0 +1,7 @@
+-- Generated by sqlxinsert-cli
+-- SQLite cannot set not null of column "color" of "cars", rebuild the table
+-- "seats" is not null in the struct, SQLite cannot add it without a default
+alter table "cars" add column "seats" integer;
+alter table "cars" add column "nickname" text;
+alter table "cars" drop column "wheels";
+create table "owners" ( "car_id" integer not null, "owner" text not null, primary key ("car_id", "owner") );
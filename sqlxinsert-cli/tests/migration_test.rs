@@ -0,0 +1,93 @@
+//! Migrations between the snapshots in `tests/fixtures` are compared with
+//! `tests/golden`, rewrite them with `UPDATE_GOLDEN=1 cargo test -p sqlxinsert-cli`
+use std::path::PathBuf;
+
+#[path = "../../tests/common/mod.rs"]
+mod common;
+
+use common::{assert_golden, path};
+use sqlxinsert_cli::{migration_file_name, render_migration, Dialect, Snapshot};
+
+fn fixture(version: &str, dialect: Dialect) -> Snapshot {
+    Snapshot::from_metadata_dir(&path(&["tests", "fixtures", version]))
+        .unwrap()
+        .dialect(dialect)
+}
+
+#[test]
+fn golden_postgres() {
+    let v1 = fixture("v1", Dialect::Postgres);
+    let v2 = fixture("v2", Dialect::Postgres);
+    assert_golden(
+        "postgres_initial.sql",
+        &render_migration(&Snapshot::default(), &v1).unwrap(),
+    );
+    assert_golden("postgres_v2.sql", &render_migration(&v1, &v2).unwrap());
+    assert_eq!(render_migration(&v2, &v2), None);
+}
+
+#[test]
+fn golden_sqlite() {
+    let v1 = fixture("v1", Dialect::Sqlite);
+    let v2 = fixture("v2", Dialect::Sqlite);
+    assert_golden("sqlite_v2.sql", &render_migration(&v1, &v2).unwrap());
+}
+
+#[test]
+fn snapshot_roundtrip() {
+    let snapshot = Snapshot::from_metadata_dir(&path(&["tests", "fixtures", "v1"])).unwrap();
+    let names = snapshot
+        .tables
+        .iter()
+        .map(|table| table.quoted())
+        .collect::<Vec<_>>();
+    assert_eq!(names, [r#""cars""#, r#""billing"."invoice""#, r#""cars""#]);
+
+    let file = std::env::temp_dir().join(format!("sqlxinsert-{}.json", std::process::id()));
+    snapshot.save(&file).unwrap();
+    assert_eq!(Snapshot::load(&file).unwrap(), snapshot);
+    std::fs::remove_file(&file).unwrap();
+
+    assert_eq!(
+        Snapshot::load(&PathBuf::from("missing.json")).unwrap(),
+        Snapshot::default()
+    );
+    assert_eq!(
+        migration_file_name("20261019120000", "Add seats"),
+        "20261019120000_add_seats.sql"
+    );
+}
+
+/// Generated SQLite migration applies to the tables of the previous snapshot
+#[tokio::test]
+async fn sqlite_migration_applies() {
+    let pool = sqlx::sqlite::SqlitePoolOptions::new()
+        .connect("sqlite::memory:")
+        .await
+        .unwrap();
+    let v1 = fixture("v1", Dialect::Sqlite);
+    for table in &v1.tables {
+        sqlx::query(&table.create).execute(&pool).await.unwrap();
+    }
+    sqlx::query("insert into cars ( name, wheels ) values ( 'Skoda', 4 )")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let migration = render_migration(&v1, &fixture("v2", Dialect::Sqlite)).unwrap();
+    for statement in migration.split(';').filter(|s| !s.trim().is_empty()) {
+        sqlx::query(statement).execute(&pool).await.unwrap();
+    }
+
+    let columns: Vec<(String,)> = sqlx::query_as("select name from pragma_table_info('cars')")
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    let columns = columns.into_iter().map(|(name,)| name).collect::<Vec<_>>();
+    assert_eq!(columns, ["id", "name", "color", "seats", "nickname"]);
+    let owners: (i64,) = sqlx::query_as("select count(*) from owners")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(owners.0, 0);
+}
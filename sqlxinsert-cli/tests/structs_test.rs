@@ -0,0 +1,43 @@
+//! Structs generated from `tests/fixtures/legacy` are compared with `tests/golden`,
+//! rewrite them with `UPDATE_GOLDEN=1 cargo test -p sqlxinsert-cli`
+use std::path::PathBuf;
+
+#[path = "../../tests/common/mod.rs"]
+mod common;
+
+use common::{assert_golden, path};
+use sqlxinsert_cli::{structs, Dialect};
+
+#[test]
+fn golden_pg_dump() {
+    let sql =
+        std::fs::read_to_string(path(&["tests", "fixtures", "legacy", "pg_dump.sql"])).unwrap();
+    assert_golden(
+        "pg_dump_structs.rs",
+        &structs::from_sql(&sql, Dialect::Postgres),
+    );
+}
+
+#[tokio::test]
+async fn golden_sqlite() {
+    let database = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("legacy.sqlite");
+    if database.exists() {
+        std::fs::remove_file(&database).unwrap();
+    }
+    let url = format!("sqlite://{}?mode=rwc", database.display());
+    let pool = sqlx::sqlite::SqlitePoolOptions::new()
+        .connect(&url)
+        .await
+        .expect("Not possible to create database");
+    let sql =
+        std::fs::read_to_string(path(&["tests", "fixtures", "legacy", "sqlite.sql"])).unwrap();
+    for statement in sql.split(';').filter(|s| !s.trim().is_empty()) {
+        sqlx::query(statement).execute(&pool).await.unwrap();
+    }
+    pool.close().await;
+
+    let source = structs::from_sqlite(&database.to_string_lossy())
+        .await
+        .unwrap();
+    assert_golden("sqlite_structs.rs", &source);
+}
@@ -0,0 +1,1091 @@
+//! Offline parser of `create table` statements, used by `#[sqlxinsert(check_schema = "...")]`
+//! and by `sqlxinsert-cli` reading `pg_dump --schema-only` files
+//!
+//! Only what is needed to know the columns and keys of each table is understood:
+//! `create table`, `drop table`, `create unique index` and `alter table` adding,
+//! dropping, renaming or retyping columns, changing defaults and `not null` and
+//! adding primary key and unique constraints, `create type ... as enum` and
+//! `alter type ... add value`. Everything else is skipped.
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Column {
+    pub name: String,
+    /// Type as written, lower case: `varchar(20)`, `timestamp with time zone`
+    pub sql_type: String,
+    pub not_null: bool,
+    pub has_default: bool,
+    /// Value is made by the database: `serial`, `nextval(...)` default, identity or generated column
+    pub generated: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    pub schema: Option<String>,
+    pub name: String,
+    pub columns: Vec<Column>,
+    /// Primary key columns, empty without primary key
+    pub primary_key: Vec<String>,
+    /// Columns of unique constraints and unique indexes
+    pub unique: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn column(&self, name: &str) -> Option<&Column> {
+        self.columns.iter().find(|column| column.name == name)
+    }
+
+    fn column_mut(&mut self, name: &str) -> Option<&mut Column> {
+        self.columns.iter_mut().find(|column| column.name == name)
+    }
+
+    fn add_column(&mut self, definition: ColumnDefinition) {
+        let ColumnDefinition {
+            column,
+            primary_key,
+            unique,
+        } = definition;
+        if primary_key {
+            self.primary_key = vec![column.name.clone()];
+        }
+        if unique {
+            self.unique.push(vec![column.name.clone()]);
+        }
+        self.columns.retain(|c| c.name != column.name);
+        self.columns.push(column);
+    }
+
+    /// `primary key (a, b)`, `unique (a)`, optionally named `constraint x ...`
+    fn add_constraint(&mut self, tokens: &[Token]) {
+        let mut parser = Parser { tokens, pos: 0 };
+        if parser.keyword("constraint") {
+            parser.ident();
+        }
+        let primary_key = parser.keywords(&["primary", "key"]);
+        if !primary_key && !parser.keyword("unique") {
+            return;
+        }
+        parser.keywords(&["nulls", "not", "distinct"]);
+        let Some(columns) = parser.parenthesized().and_then(ident_list) else {
+            return;
+        };
+        if primary_key {
+            for name in &columns {
+                if let Some(column) = self.column_mut(name) {
+                    column.not_null = true;
+                }
+            }
+            self.primary_key = columns;
+        } else if !self.unique.contains(&columns) {
+            self.unique.push(columns);
+        }
+    }
+}
+
+/// `create type car_color as enum ( 'red', 'blue' )`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Enum {
+    pub schema: Option<String>,
+    pub name: String,
+    pub values: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct Schema {
+    pub tables: Vec<Table>,
+    pub enums: Vec<Enum>,
+    /// Files read, tracked by the compiler with `include_bytes!`
+    pub files: Vec<PathBuf>,
+}
+
+impl Schema {
+    /// `schema.sql`, or a migrations directory applied in file name order
+    /// ( `*.down.sql` of reversible migrations are skipped )
+    pub fn load(path: &Path) -> Result<Schema, String> {
+        let mut files = if path.is_dir() {
+            std::fs::read_dir(path)
+                .map_err(|e| format!("cannot read {}: {}", path.display(), e))?
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| {
+                    let name = path.file_name().unwrap_or_default().to_string_lossy();
+                    name.ends_with(".sql") && !name.ends_with(".down.sql")
+                })
+                .collect::<Vec<_>>()
+        } else {
+            vec![path.to_path_buf()]
+        };
+        files.sort();
+
+        let mut schema = Schema::default();
+        for file in files {
+            let sql = std::fs::read_to_string(&file)
+                .map_err(|e| format!("cannot read {}: {}", file.display(), e))?;
+            schema.apply(&sql);
+            schema.files.push(file);
+        }
+        Ok(schema)
+    }
+
+    /// Apply statements of `sql` in order
+    pub fn apply(&mut self, sql: &str) {
+        for statement in tokenize(sql).split(|token| *token == Token::Punct(';')) {
+            self.apply_statement(statement);
+        }
+    }
+
+    /// Table `name`, in `schema` or ( when `None` ) unqualified or in `public`
+    pub fn table(&self, schema: Option<&str>, name: &str) -> Option<&Table> {
+        self.tables.iter().find(|table| {
+            table.name == name
+                && match (schema, table.schema.as_deref()) {
+                    (Some(schema), Some(table_schema)) => schema == table_schema,
+                    (Some(_), None) => false,
+                    (None, table_schema) => {
+                        table_schema.is_none() || table_schema == Some("public")
+                    }
+                }
+        })
+    }
+
+    fn position(&self, (schema, name): &(Option<String>, String)) -> Option<usize> {
+        let table = self.table(schema.as_deref(), name)?;
+        self.tables.iter().position(|t| std::ptr::eq(t, table))
+    }
+
+    fn apply_statement(&mut self, tokens: &[Token]) {
+        let mut parser = Parser { tokens, pos: 0 };
+        if parser.keyword("create") {
+            parser.skip_keywords(&["temp", "temporary", "unlogged"]);
+            if parser.keyword("unique") {
+                self.create_unique_index(parser);
+                return;
+            }
+            if parser.keyword("type") {
+                self.create_enum(parser);
+                return;
+            }
+            if !parser.keyword("table") {
+                return;
+            }
+            parser.keywords(&["if", "not", "exists"]);
+            let Some((schema, name)) = parser.qualified_name() else {
+                return;
+            };
+            let Some(body) = parser.parenthesized() else {
+                return;
+            };
+            let mut table = Table {
+                schema,
+                name,
+                columns: Vec::new(),
+                primary_key: Vec::new(),
+                unique: Vec::new(),
+            };
+            for part in split_commas(body) {
+                match column_definition(part) {
+                    Some(definition) => table.add_column(definition),
+                    None => table.add_constraint(part),
+                }
+            }
+            if let Some(position) = self.position(&(table.schema.clone(), table.name.clone())) {
+                self.tables.remove(position);
+            }
+            self.tables.push(table);
+        } else if parser.keyword("drop") {
+            if !parser.keyword("table") {
+                return;
+            }
+            parser.keywords(&["if", "exists"]);
+            for name in split_commas(parser.rest()) {
+                let name = Parser {
+                    tokens: name,
+                    pos: 0,
+                }
+                .qualified_name();
+                if let Some(position) = name.and_then(|name| self.position(&name)) {
+                    self.tables.remove(position);
+                }
+            }
+        } else if parser.keyword("alter") {
+            if parser.keyword("type") {
+                self.alter_enum(parser);
+                return;
+            }
+            if !parser.keyword("table") {
+                return;
+            }
+            parser.keywords(&["if", "exists"]);
+            parser.keyword("only");
+            let Some(name) = parser.qualified_name() else {
+                return;
+            };
+            let Some(position) = self.position(&name) else {
+                return;
+            };
+            for action in split_commas(parser.rest()) {
+                alter_table(&mut self.tables[position], action);
+            }
+        }
+    }
+}
+
+impl Schema {
+    /// Enum `name`, in `schema` or ( when `None` ) unqualified or in `public`
+    pub fn enum_type(&self, schema: Option<&str>, name: &str) -> Option<&Enum> {
+        self.enums.iter().find(|enum_type| {
+            enum_type.name == name
+                && match (schema, enum_type.schema.as_deref()) {
+                    (Some(schema), Some(enum_schema)) => schema == enum_schema,
+                    (Some(_), None) => false,
+                    (None, enum_schema) => enum_schema.is_none() || enum_schema == Some("public"),
+                }
+        })
+    }
+
+    /// `create type [schema.]name as enum ( 'a', 'b' )`, other types are skipped
+    fn create_enum(&mut self, mut parser: Parser) {
+        let Some((schema, name)) = parser.qualified_name() else {
+            return;
+        };
+        if !parser.keywords(&["as", "enum"]) {
+            return;
+        }
+        let Some(values) = parser.parenthesized() else {
+            return;
+        };
+        let values = split_commas(values)
+            .into_iter()
+            .filter_map(|value| match value {
+                [Token::Str(value)] => Some(value.clone()),
+                _ => None,
+            })
+            .collect();
+        self.enums
+            .retain(|e| !(e.schema == schema && e.name == name));
+        self.enums.push(Enum {
+            schema,
+            name,
+            values,
+        });
+    }
+
+    /// `alter type name add value [if not exists] 'c' [before | after 'b']`,
+    /// without a position ( or an unknown neighbour ) the new value is appended
+    fn alter_enum(&mut self, mut parser: Parser) {
+        let Some((schema, name)) = parser.qualified_name() else {
+            return;
+        };
+        if !parser.keywords(&["add", "value"]) {
+            return;
+        }
+        parser.keywords(&["if", "not", "exists"]);
+        let Some(Token::Str(value)) = parser.peek() else {
+            return;
+        };
+        parser.skip();
+        let before = parser.keyword("before");
+        let neighbour = match (before || parser.keyword("after"), parser.peek()) {
+            (true, Some(Token::Str(neighbour))) => Some(neighbour),
+            _ => None,
+        };
+        let position = self
+            .enums
+            .iter()
+            .position(|e| Some(e) == self.enum_type(schema.as_deref(), &name));
+        if let Some(position) = position {
+            let values = &mut self.enums[position].values;
+            if values.contains(value) {
+                return;
+            }
+            let index = neighbour
+                .and_then(|neighbour| values.iter().position(|v| v == neighbour))
+                .map_or(values.len(), |index| index + usize::from(!before));
+            values.insert(index, value.clone());
+        }
+    }
+
+    /// `create unique index [name] on table ( a, b )`, expression indexes are skipped
+    fn create_unique_index(&mut self, mut parser: Parser) {
+        if !parser.keyword("index") {
+            return;
+        }
+        parser.keyword("concurrently");
+        parser.keywords(&["if", "not", "exists"]);
+        if !parser.is_keyword(&["on"]) {
+            parser.ident();
+        }
+        if !parser.keyword("on") {
+            return;
+        }
+        parser.keyword("only");
+        let Some(name) = parser.qualified_name() else {
+            return;
+        };
+        if parser.keyword("using") {
+            parser.ident();
+        }
+        let Some(columns) = parser.parenthesized().and_then(ident_list) else {
+            return;
+        };
+        // partial indexes are not usable as `on conflict` target without the predicate
+        if parser.is_keyword(&["where"]) {
+            return;
+        }
+        if let Some(position) = self.position(&name) {
+            let table = &mut self.tables[position];
+            if !table.unique.contains(&columns) {
+                table.unique.push(columns);
+            }
+        }
+    }
+}
+
+/// One `alter table` action
+fn alter_table(table: &mut Table, action: &[Token]) {
+    let mut parser = Parser {
+        tokens: action,
+        pos: 0,
+    };
+    if parser.keyword("add") {
+        if parser.is_keyword(TABLE_CONSTRAINTS) {
+            table.add_constraint(parser.rest());
+            return;
+        }
+        parser.keyword("column");
+        parser.keywords(&["if", "not", "exists"]);
+        if let Some(definition) = column_definition(parser.rest()) {
+            table.add_column(definition);
+        }
+    } else if parser.keyword("drop") {
+        if parser.is_keyword(&["constraint"]) {
+            return;
+        }
+        parser.keyword("column");
+        parser.keywords(&["if", "exists"]);
+        if let Some(name) = parser.ident() {
+            table.columns.retain(|c| c.name != name);
+            // constraints on the column are dropped with it
+            if table.primary_key.contains(&name) {
+                table.primary_key.clear();
+            }
+            table.unique.retain(|columns| !columns.contains(&name));
+        }
+    } else if parser.keyword("rename") {
+        if parser.keyword("to") {
+            if let Some(name) = parser.ident() {
+                table.name = name;
+            }
+            return;
+        }
+        if parser.is_keyword(&["constraint"]) {
+            return;
+        }
+        parser.keyword("column");
+        let (Some(from), true, Some(to)) = (parser.ident(), parser.keyword("to"), parser.ident())
+        else {
+            return;
+        };
+        for key in table
+            .unique
+            .iter_mut()
+            .chain(std::iter::once(&mut table.primary_key))
+        {
+            for column in key.iter_mut().filter(|column| **column == from) {
+                *column = to.clone();
+            }
+        }
+        if let Some(column) = table.column_mut(&from) {
+            column.name = to;
+        }
+    } else if parser.keyword("alter") {
+        parser.keyword("column");
+        let Some(column) = parser.ident().and_then(|name| table.column_mut(&name)) else {
+            return;
+        };
+        if parser.keywords(&["set", "default"]) {
+            column.has_default = true;
+            column.generated = is_nextval(parser.rest());
+        } else if parser.keywords(&["drop", "default"]) {
+            column.has_default = false;
+            column.generated = false;
+        } else if parser.keywords(&["set", "not", "null"]) {
+            column.not_null = true;
+        } else if parser.keywords(&["drop", "not", "null"]) {
+            column.not_null = false;
+        } else if parser.keyword("add") && parser.keyword("generated") {
+            column.generated = true;
+        } else {
+            parser.keywords(&["set", "data"]);
+            if parser.keyword("type") {
+                let rest = parser.rest();
+                // `using` expression converting existing values
+                let end = rest
+                    .iter()
+                    .position(|token| *token == Token::Word("using".to_string()))
+                    .unwrap_or(rest.len());
+                column.sql_type = render_type(&rest[..end]);
+            }
+        }
+    }
+}
+
+/// Keywords ending the type of a column definition
+const COLUMN_CONSTRAINTS: &[&str] = &[
+    "constraint",
+    "not",
+    "null",
+    "default",
+    "primary",
+    "unique",
+    "references",
+    "check",
+    "generated",
+    "collate",
+];
+
+/// Keywords starting a table constraint
+const TABLE_CONSTRAINTS: &[&str] = &[
+    "constraint",
+    "primary",
+    "unique",
+    "foreign",
+    "check",
+    "exclude",
+    "like",
+];
+
+/// Column with its inline `primary key` and `unique` constraints
+struct ColumnDefinition {
+    column: Column,
+    primary_key: bool,
+    unique: bool,
+}
+
+/// `name type ...` -> column, `None` for table constraints
+fn column_definition(tokens: &[Token]) -> Option<ColumnDefinition> {
+    let mut parser = Parser { tokens, pos: 0 };
+    if parser.is_keyword(TABLE_CONSTRAINTS) {
+        return None;
+    }
+    let name = parser.ident()?;
+    let rest = parser.rest();
+    let end = rest
+        .iter()
+        .position(|token| matches!(token, Token::Word(word) if COLUMN_CONSTRAINTS.contains(&word.as_str())))
+        .unwrap_or(rest.len());
+    let sql_type = render_type(&rest[..end]);
+    let serial = matches!(
+        sql_type.as_str(),
+        "serial" | "serial2" | "serial4" | "serial8" | "smallserial" | "bigserial"
+    );
+    let mut definition = ColumnDefinition {
+        column: Column {
+            name,
+            sql_type,
+            not_null: serial,
+            has_default: serial,
+            generated: serial,
+        },
+        primary_key: false,
+        unique: false,
+    };
+    let mut parser = Parser {
+        tokens: &rest[end..],
+        pos: 0,
+    };
+    while parser.peek().is_some() {
+        if parser.keywords(&["not", "null"]) {
+            definition.column.not_null = true;
+        } else if parser.keywords(&["primary", "key"]) {
+            definition.primary_key = true;
+            definition.column.not_null = true;
+        } else if parser.keyword("unique") {
+            definition.unique = true;
+        } else if parser.keyword("default") {
+            definition.column.has_default = true;
+            // default expression runs until the next constraint
+            let start = parser.pos;
+            while parser.peek().is_some() && !parser.is_keyword(COLUMN_CONSTRAINTS) {
+                parser.skip();
+            }
+            definition.column.generated |= is_nextval(&parser.tokens[start..parser.pos]);
+        } else if parser.keyword("generated") {
+            // `generated always as identity`, `generated always as ( expr ) stored`
+            definition.column.generated = true;
+        } else {
+            parser.skip();
+        }
+    }
+    Some(definition)
+}
+
+fn is_nextval(tokens: &[Token]) -> bool {
+    tokens.contains(&Token::Word("nextval".to_string()))
+}
+
+/// `( a, "B" )` -> `["a", "B"]`, `None` when an item is an expression
+fn ident_list(tokens: &[Token]) -> Option<Vec<String>> {
+    split_commas(tokens)
+        .into_iter()
+        .map(|item| match item {
+            [Token::Word(word)] | [Token::Quoted(word)] => Some(word.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Type tokens -> `varchar(20)`, `double precision`, `text[]`
+fn render_type(tokens: &[Token]) -> String {
+    let mut sql_type = String::new();
+    for token in tokens {
+        match token {
+            Token::Word(word) | Token::Quoted(word) => {
+                if sql_type.ends_with(|c: char| c.is_ascii_alphanumeric() || c == '_' || c == '"') {
+                    sql_type.push(' ');
+                }
+                sql_type.push_str(word);
+            }
+            Token::Punct(c) => sql_type.push(*c),
+            Token::Str(_) => {}
+        }
+    }
+    sql_type
+}
+
+/// Split on commas outside parentheses
+fn split_commas(tokens: &[Token]) -> Vec<&[Token]> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Punct('(') => depth += 1,
+            Token::Punct(')') => depth -= 1,
+            Token::Punct(',') if depth == 0 => {
+                parts.push(&tokens[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&tokens[start..]);
+    parts.into_iter().filter(|part| !part.is_empty()).collect()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// Unquoted word, lower cased like Postgres does
+    Word(String),
+    /// `"Quoted"` identifier, case kept
+    Quoted(String),
+    /// `'string'`
+    Str(String),
+    Punct(char),
+}
+
+fn tokenize(sql: &str) -> Vec<Token> {
+    let chars = sql.chars().collect::<Vec<_>>();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '-' && chars.get(i + 1) == Some(&'-') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '\\' {
+            // psql meta-command ( `\restrict` in `pg_dump` output ) runs to the end of the line
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '/' && chars.get(i + 1) == Some(&'*') {
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                i += 1;
+            }
+            i += 2;
+        } else if c == '"' || c == '\'' {
+            // doubled quote is an escaped quote
+            let mut value = String::new();
+            i += 1;
+            while i < chars.len() {
+                if chars[i] == c && chars.get(i + 1) == Some(&c) {
+                    value.push(c);
+                    i += 2;
+                } else if chars[i] == c {
+                    i += 1;
+                    break;
+                } else {
+                    value.push(chars[i]);
+                    i += 1;
+                }
+            }
+            tokens.push(if c == '"' {
+                Token::Quoted(value)
+            } else {
+                Token::Str(value)
+            });
+        } else if let Some(end) = dollar_quote_end(&chars, i) {
+            // `$$ ... $$`, `$body$ ... $body$` function bodies, contents are not needed
+            tokens.push(Token::Str(String::new()));
+            i = end;
+        } else if c.is_alphanumeric() || c == '_' || c == '$' {
+            let start = i;
+            while i < chars.len()
+                && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '$')
+            {
+                i += 1;
+            }
+            let word = chars[start..i].iter().collect::<String>();
+            tokens.push(Token::Word(word.to_lowercase()));
+        } else {
+            tokens.push(Token::Punct(c));
+            i += 1;
+        }
+    }
+    tokens
+}
+
+/// End of the dollar quoted string starting at `start` ( after its closing tag ),
+/// `None` when there is no dollar quote at `start`
+fn dollar_quote_end(chars: &[char], start: usize) -> Option<usize> {
+    if chars[start] != '$' {
+        return None;
+    }
+    let mut end = start + 1;
+    while end < chars.len() && (chars[end].is_alphanumeric() || chars[end] == '_') {
+        end += 1;
+    }
+    // `$1` is a parameter, not a tag
+    if chars.get(end) != Some(&'$') || chars.get(start + 1).is_some_and(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let tag = &chars[start..=end];
+    let mut i = end + 1;
+    while i + tag.len() <= chars.len() {
+        if &chars[i..i + tag.len()] == tag {
+            return Some(i + tag.len());
+        }
+        i += 1;
+    }
+    Some(chars.len())
+}
+
+/// Replace the value placeholder of an `insert_expr` / `update_expr` with `value`
+///
+/// The placeholder is a standalone `$`: not inside `'...'`, `"..."`, dollar quoted text or
+/// a comment, not part of an identifier and not followed by a digit or another `$`.
+///
+/// ```rust
+/// use sqlxinsert_schema::replace_placeholder;
+///
+/// assert_eq!(replace_placeholder("lower($)", "$3"), "lower($3)");
+/// assert_eq!(replace_placeholder("$ || '$'", "$1"), "$1 || '$'");
+/// assert_eq!(replace_placeholder("coalesce($, $$ $ $$)", "$2"), "coalesce($2, $$ $ $$)");
+/// ```
+pub fn replace_placeholder(expr: &str, value: &str) -> String {
+    let chars = expr.chars().collect::<Vec<_>>();
+    let positions = placeholders(&chars);
+    let mut replaced = String::with_capacity(expr.len() + positions.len() * value.len());
+    for (i, c) in chars.iter().enumerate() {
+        if positions.contains(&i) {
+            replaced.push_str(value);
+        } else {
+            replaced.push(*c);
+        }
+    }
+    replaced
+}
+
+/// `expr` has a value placeholder, see `replace_placeholder`
+pub fn has_placeholder(expr: &str) -> bool {
+    !placeholders(&expr.chars().collect::<Vec<_>>()).is_empty()
+}
+
+/// Indexes of the placeholders in `chars`
+fn placeholders(chars: &[char]) -> Vec<usize> {
+    let mut positions = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '-' && chars.get(i + 1) == Some(&'-') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '/' && chars.get(i + 1) == Some(&'*') {
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                i += 1;
+            }
+            i += 2;
+        } else if c == '"' || c == '\'' {
+            // doubled quote is an escaped quote, it just closes and opens again
+            i += 1;
+            while i < chars.len() && chars[i] != c {
+                i += 1;
+            }
+            i += 1;
+        } else if c.is_alphanumeric() || c == '_' {
+            // `$` inside identifiers: `a$b`
+            while i < chars.len()
+                && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '$')
+            {
+                i += 1;
+            }
+        } else if let Some(end) = dollar_quote_end(chars, i) {
+            i = end;
+        } else if c == '$' {
+            let next = chars.get(i + 1);
+            if !next.is_some_and(|next| next.is_ascii_digit() || *next == '$') {
+                positions.push(i);
+            }
+            i += 1;
+            // `$1`
+            while i < chars.len() && chars[i].is_ascii_digit() {
+                i += 1;
+            }
+        } else {
+            i += 1;
+        }
+    }
+    positions
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&'a Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn is_keyword(&self, keywords: &[&str]) -> bool {
+        matches!(self.peek(), Some(Token::Word(word)) if keywords.contains(&word.as_str()))
+    }
+
+    /// Consume `keyword` if it is next
+    fn keyword(&mut self, keyword: &str) -> bool {
+        let found = self.is_keyword(&[keyword]);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    /// Consume all of `keywords` if they follow in order
+    fn keywords(&mut self, keywords: &[&str]) -> bool {
+        let start = self.pos;
+        if keywords.iter().all(|keyword| self.keyword(keyword)) {
+            true
+        } else {
+            self.pos = start;
+            false
+        }
+    }
+
+    fn skip(&mut self) {
+        if self.parenthesized().is_none() {
+            self.pos += 1;
+        }
+    }
+
+    fn skip_keywords(&mut self, keywords: &[&str]) {
+        while self.is_keyword(keywords) {
+            self.pos += 1;
+        }
+    }
+
+    fn ident(&mut self) -> Option<String> {
+        match self.peek()? {
+            Token::Word(word) | Token::Quoted(word) => {
+                self.pos += 1;
+                Some(word.clone())
+            }
+            _ => None,
+        }
+    }
+
+    /// `name` or `schema.name`
+    fn qualified_name(&mut self) -> Option<(Option<String>, String)> {
+        let first = self.ident()?;
+        if self.peek() == Some(&Token::Punct('.')) {
+            self.pos += 1;
+            Some((Some(first), self.ident()?))
+        } else {
+            Some((None, first))
+        }
+    }
+
+    /// Tokens inside the next `( ... )`
+    fn parenthesized(&mut self) -> Option<&'a [Token]> {
+        if self.peek() != Some(&Token::Punct('(')) {
+            return None;
+        }
+        let start = self.pos + 1;
+        let mut depth = 0;
+        for (i, token) in self.tokens[self.pos..].iter().enumerate() {
+            match token {
+                Token::Punct('(') => depth += 1,
+                Token::Punct(')') => {
+                    depth -= 1;
+                    if depth == 0 {
+                        let end = self.pos + i;
+                        self.pos = end + 1;
+                        return Some(&self.tokens[start..end]);
+                    }
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    fn rest(&mut self) -> &'a [Token] {
+        let rest = &self.tokens[self.pos..];
+        self.pos = self.tokens.len();
+        rest
+    }
+}
+
+/// Comparable form of a Postgres type: aliases resolved, modifiers dropped
+///
+/// `int4` -> `integer`, `varchar(20)` -> `text`, `int4[]` -> `integer[]`
+///
+/// Arrays are written with `[]`, a leading `_` is part of the type name:
+/// the catalog's `_int4` is `int4[]` only when the catalog says it is an array
+///
+/// ```rust
+/// use sqlxinsert_schema::canonical_type;
+///
+/// assert_eq!(canonical_type("int4"), "integer");
+/// assert_eq!(canonical_type("character varying(40)"), "text");
+/// assert_eq!(canonical_type("timestamptz[]"), "timestamptz[]");
+/// assert_eq!(canonical_type("_name"), "_name");
+/// ```
+pub fn canonical_type(sql_type: &str) -> String {
+    let sql_type = sql_type.trim().to_lowercase();
+    if let Some(element) = sql_type.strip_suffix("[]") {
+        return format!("{}[]", canonical_type(element));
+    }
+    // varchar(20) -> varchar, numeric(10, 2) -> numeric
+    let base = match sql_type.find('(') {
+        Some(start) => {
+            let end = sql_type.rfind(')').map_or(sql_type.len(), |end| end + 1);
+            format!("{}{}", sql_type[..start].trim_end(), &sql_type[end..])
+        }
+        None => sql_type,
+    };
+    let base = base.split_whitespace().collect::<Vec<_>>().join(" ");
+    let canonical = match base.as_str() {
+        "int" | "int4" | "integer" | "serial" | "serial4" => "integer",
+        "int8" | "bigint" | "bigserial" | "serial8" => "bigint",
+        "int2" | "smallint" | "smallserial" | "serial2" => "smallint",
+        "float4" | "real" => "real",
+        "float8" | "double precision" | "float" => "double precision",
+        "bool" | "boolean" => "boolean",
+        "text" | "varchar" | "character varying" | "char" | "character" | "bpchar" | "citext"
+        | "name" => "text",
+        "timestamptz" | "timestamp with time zone" => "timestamptz",
+        "timestamp" | "timestamp without time zone" => "timestamp",
+        "timetz" | "time with time zone" => "timetz",
+        "time" | "time without time zone" => "time",
+        "decimal" | "numeric" => "numeric",
+        other => other,
+    };
+    canonical.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SQL: &str = r#"
+        -- cars
+        CREATE TABLE IF NOT EXISTS cars (
+            id SERIAL PRIMARY KEY,
+            name VARCHAR(40) NOT NULL UNIQUE,
+            "Color" text DEFAULT 'red, or ''blue''',
+            price NUMERIC(10, 2),
+            tags text[] NOT NULL,
+            CONSTRAINT cars_name_check CHECK (length(name) > 0)
+        );
+        /* billing */
+        create table billing.invoice ( id bigserial, created_at timestamp with time zone not null, primary key (id) );
+        create index cars_name on cars (name);
+    "#;
+
+    #[test]
+    fn create_table_test() {
+        let mut schema = Schema::default();
+        schema.apply(SQL);
+        let cars = schema.table(None, "cars").unwrap();
+        let names = cars
+            .columns
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["id", "name", "Color", "price", "tags"]);
+        assert_eq!(cars.column("name").unwrap().sql_type, "varchar(40)");
+        assert_eq!(cars.column("price").unwrap().sql_type, "numeric(10,2)");
+        assert_eq!(cars.column("tags").unwrap().sql_type, "text[]");
+
+        let invoice = schema.table(Some("billing"), "invoice").unwrap();
+        assert_eq!(
+            invoice.column("created_at").unwrap().sql_type,
+            "timestamp with time zone"
+        );
+        assert!(schema.table(None, "invoice").is_none());
+    }
+
+    #[test]
+    fn migrations_test() {
+        let mut schema = Schema::default();
+        schema.apply(SQL);
+        schema.apply(
+            "ALTER TABLE cars ADD COLUMN wheels int2 NOT NULL DEFAULT 4, DROP COLUMN price;
+             alter table cars rename column \"Color\" to color;
+             alter table cars alter column name type text;
+             alter table cars add constraint wheels_check check (wheels > 0);
+             drop table if exists billing.invoice;",
+        );
+        let cars = schema.table(None, "cars").unwrap();
+        let names = cars
+            .columns
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["id", "name", "color", "tags", "wheels"]);
+        assert_eq!(cars.column("name").unwrap().sql_type, "text");
+        assert!(schema.table(Some("billing"), "invoice").is_none());
+    }
+
+    #[test]
+    fn keys_test() {
+        let mut schema = Schema::default();
+        schema.apply(SQL);
+        let cars = schema.table(None, "cars").unwrap();
+        assert_eq!(cars.primary_key, vec!["id"]);
+        assert_eq!(cars.unique, vec![vec!["name"]]);
+        let id = cars.column("id").unwrap();
+        assert!(id.not_null && id.generated);
+        let color = cars.column("Color").unwrap();
+        assert!(!color.not_null && color.has_default && !color.generated);
+        assert!(cars.column("tags").unwrap().not_null);
+
+        let invoice = schema.table(Some("billing"), "invoice").unwrap();
+        assert_eq!(invoice.primary_key, vec!["id"]);
+        assert!(invoice.column("id").unwrap().not_null);
+    }
+
+    #[test]
+    fn pg_dump_test() {
+        let mut schema = Schema::default();
+        schema.apply(
+            r#"
+            \restrict abc
+            CREATE FUNCTION public.touch() RETURNS trigger LANGUAGE plpgsql AS $$
+            BEGIN
+                NEW.updated_at = now(); -- create table nope ( x int );
+                RETURN NEW;
+            END;
+            $$;
+            CREATE TABLE public.cars (
+                id integer NOT NULL,
+                name character varying(40) NOT NULL,
+                total double precision GENERATED ALWAYS AS ((price * (1.2)::double precision)) STORED,
+                price double precision
+            );
+            CREATE TYPE public.car_color AS ENUM ( 'red', 'it''s blue' );
+            CREATE TYPE public.point2 AS ( x integer, y integer );
+            ALTER TYPE car_color ADD VALUE IF NOT EXISTS 'green' AFTER 'red';
+            CREATE TABLE public.owners ( id bigint NOT NULL, car_id integer );
+            ALTER TABLE ONLY public.cars ALTER COLUMN id SET DEFAULT nextval('public.cars_id_seq'::regclass);
+            ALTER TABLE public.owners ALTER COLUMN id ADD GENERATED ALWAYS AS IDENTITY (
+                SEQUENCE NAME public.owners_id_seq
+            );
+            ALTER TABLE ONLY public.cars
+                ADD CONSTRAINT cars_pkey PRIMARY KEY (id);
+            CREATE UNIQUE INDEX cars_name ON public.cars USING btree (name);
+            CREATE UNIQUE INDEX cars_lower_name ON public.cars USING btree (lower((name)::text));
+            CREATE UNIQUE INDEX owners_car ON public.owners USING btree (car_id) WHERE (car_id IS NOT NULL);
+            ALTER TABLE ONLY public.owners
+                ADD CONSTRAINT owners_car_id_fkey FOREIGN KEY (car_id) REFERENCES public.cars(id);
+            "#,
+        );
+        assert!(schema.table(None, "nope").is_none());
+        let cars = schema.table(None, "cars").unwrap();
+        assert_eq!(cars.primary_key, vec!["id"]);
+        assert_eq!(cars.unique, vec![vec!["name"]]);
+        let generated = cars.columns.iter().map(|c| c.generated).collect::<Vec<_>>();
+        assert_eq!(generated, vec![true, false, true, false]);
+
+        let owners = schema.table(None, "owners").unwrap();
+        assert!(owners.column("id").unwrap().generated);
+        assert!(owners.primary_key.is_empty() && owners.unique.is_empty());
+
+        assert_eq!(schema.enums.len(), 1);
+        let color = schema.enum_type(None, "car_color").unwrap();
+        assert_eq!(color.values, vec!["red", "green", "it's blue"]);
+    }
+
+    #[test]
+    fn enum_migrations_test() {
+        let mut schema = Schema::default();
+        for migration in [
+            "CREATE TYPE car_color AS ENUM ( 'red', 'blue' );",
+            "ALTER TYPE car_color ADD VALUE 'green';",
+            "ALTER TYPE car_color ADD VALUE 'black' BEFORE 'red';",
+            "ALTER TYPE car_color ADD VALUE IF NOT EXISTS 'white' AFTER 'red';",
+            "ALTER TYPE car_color ADD VALUE IF NOT EXISTS 'green' BEFORE 'black';",
+            "ALTER TYPE car_color ADD VALUE 'grey' AFTER 'silver';",
+        ] {
+            schema.apply(migration);
+        }
+        let color = schema.enum_type(None, "car_color").unwrap();
+        assert_eq!(
+            color.values,
+            vec!["black", "red", "white", "blue", "green", "grey"]
+        );
+    }
+
+    #[test]
+    fn canonical_type_test() {
+        assert_eq!(canonical_type("SERIAL"), "integer");
+        assert_eq!(canonical_type("varchar(40)"), "text");
+        assert_eq!(canonical_type("numeric(10,2)"), "numeric");
+        assert_eq!(canonical_type("timestamp with time zone"), "timestamptz");
+        assert_eq!(canonical_type("int4[]"), "integer[]");
+        assert_eq!(canonical_type("_int4"), "_int4");
+        assert_eq!(canonical_type("_name"), "_name");
+        assert_eq!(canonical_type("json"), "json");
+        assert_eq!(canonical_type("jsonb"), "jsonb");
+        assert_eq!(canonical_type("character varying(10)[]"), "text[]");
+        assert_eq!(canonical_type("car_color"), "car_color");
+    }
+
+    #[test]
+    fn placeholder_test() {
+        assert_eq!(replace_placeholder("$", "$1"), "$1");
+        assert_eq!(replace_placeholder("$::jsonb", "$2"), "$2::jsonb");
+        // literals, identifiers, parameters and comments keep their `$`
+        assert_eq!(
+            replace_placeholder(r#"concat($, '$', "a$", a$b, $1) -- $"#, "$3"),
+            r#"concat($3, '$', "a$", a$b, $1) -- $"#
+        );
+        assert_eq!(
+            replace_placeholder("coalesce($, $tag$ $ $tag$) /* $ */", "$4"),
+            "coalesce($4, $tag$ $ $tag$) /* $ */"
+        );
+        assert_eq!(
+            replace_placeholder("'it''s $' || $", "$5"),
+            "'it''s $' || $5"
+        );
+        assert!(has_placeholder("lower($)"));
+        assert!(!has_placeholder("lower('$')"));
+        assert!(!has_placeholder("$1"));
+    }
+}
@@ -0,0 +1,200 @@
+//! Identifiers ( tables, columns ) used by the generated SQL.
+use std::borrow::Cow;
+use std::fmt;
+use std::str::FromStr;
+
+/// Longest identifier Postgres keeps without truncating ( `NAMEDATALEN - 1` ).
+pub const MAX_IDENT_LEN: usize = 63;
+
+/// SQL dialect deciding how identifiers are quoted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dialect {
+    Postgres,
+    Sqlite,
+    MySql,
+}
+
+/// Quote identifier, doubling any quote character inside it
+///
+/// ```rust
+/// use sqlxinsert::ident::{quote, Dialect};
+///
+/// assert_eq!(quote("order", Dialect::Postgres), r#""order""#);
+/// assert_eq!(quote("order", Dialect::MySql), "`order`");
+/// assert_eq!(quote(r#"a"b"#, Dialect::Sqlite), r#""a""b""#);
+/// ```
+pub fn quote(ident: &str, dialect: Dialect) -> String {
+    let q = match dialect {
+        Dialect::Postgres | Dialect::Sqlite => '"',
+        Dialect::MySql => '`',
+    };
+    let mut quoted = String::with_capacity(ident.len() + 2);
+    quoted.push(q);
+    for c in ident.chars() {
+        if c == q {
+            quoted.push(q);
+        }
+        quoted.push(c);
+    }
+    quoted.push(q);
+    quoted
+}
+
+/// Check that `ident` is a plain SQL identifier: `[A-Za-z_][A-Za-z0-9_$]*`
+///
+/// ```rust
+/// assert!(sqlxinsert::ident::validate("events_2026_10").is_ok());
+/// assert!(sqlxinsert::ident::validate("cars; drop table cars").is_err());
+/// ```
+pub fn validate(ident: &str) -> eyre::Result<()> {
+    let mut chars = ident.chars();
+    match chars.next() {
+        None => eyre::bail!("identifier must not be empty"),
+        Some(c) if !(c.is_ascii_alphabetic() || c == '_') => {
+            eyre::bail!(
+                "identifier `{}` must start with a letter or underscore",
+                ident
+            )
+        }
+        _ => {}
+    }
+    if let Some(c) = chars.find(|c| !(c.is_ascii_alphanumeric() || *c == '_' || *c == '$')) {
+        eyre::bail!("identifier `{}` contains invalid character `{}`", ident, c);
+    }
+    if ident.len() > MAX_IDENT_LEN {
+        eyre::bail!(
+            "identifier `{}` is longer than {} characters",
+            ident,
+            MAX_IDENT_LEN
+        );
+    }
+    Ok(())
+}
+
+/// Optionally schema-qualified table name
+///
+/// Created either by the derive ( `Car::TABLE` ) or at runtime for tables whose
+/// name is only known when the query runs ( partitions, shards ).
+/// Display renders the Postgres/SQLite quoted form: `"billing"."invoice"`.
+///
+/// ```rust
+/// use sqlxinsert::TableRef;
+///
+/// let table: TableRef = "billing.events_2026_10".parse().unwrap();
+/// assert_eq!(table.schema(), Some("billing"));
+/// assert_eq!(table.name(), "events_2026_10");
+/// assert_eq!(table.to_string(), r#""billing"."events_2026_10""#);
+///
+/// assert!(TableRef::new("cars where 1=1").is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TableRef {
+    schema: Option<Cow<'static, str>>,
+    name: Cow<'static, str>,
+}
+
+impl TableRef {
+    /// Table in the default search path
+    pub fn new<T: Into<String>>(name: T) -> eyre::Result<Self> {
+        let name = name.into();
+        validate(&name)?;
+        Ok(TableRef {
+            schema: None,
+            name: Cow::Owned(name),
+        })
+    }
+
+    /// Table in the given schema
+    pub fn with_schema<S: Into<String>, T: Into<String>>(schema: S, name: T) -> eyre::Result<Self> {
+        let schema = schema.into();
+        validate(&schema)?;
+        let mut table = TableRef::new(name)?;
+        table.schema = Some(Cow::Owned(schema));
+        Ok(table)
+    }
+
+    /// Used by the derive, which validates identifiers at compile time.
+    #[doc(hidden)]
+    pub const fn from_static(schema: Option<&'static str>, name: &'static str) -> Self {
+        let schema = match schema {
+            Some(schema) => Some(Cow::Borrowed(schema)),
+            None => None,
+        };
+        TableRef {
+            schema,
+            name: Cow::Borrowed(name),
+        }
+    }
+
+    pub fn schema(&self) -> Option<&str> {
+        self.schema.as_deref()
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Quoted ( and schema qualified ) name for given dialect
+    pub fn quoted(&self, dialect: Dialect) -> String {
+        match &self.schema {
+            Some(schema) => format!("{}.{}", quote(schema, dialect), quote(&self.name, dialect)),
+            None => quote(&self.name, dialect),
+        }
+    }
+}
+
+/// `table` or `schema.table`
+impl FromStr for TableRef {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> eyre::Result<Self> {
+        match s.split_once('.') {
+            Some((schema, name)) => TableRef::with_schema(schema, name),
+            None => TableRef::new(s),
+        }
+    }
+}
+
+impl fmt::Display for TableRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.quoted(Dialect::Postgres))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_test() {
+        assert!(validate("cars").is_ok());
+        assert!(validate("_cars$1").is_ok());
+        assert!(validate("").is_err());
+        assert!(validate("1cars").is_err());
+        assert!(validate("cars\"").is_err());
+        assert!(validate("billing.cars").is_err());
+        assert!(validate(&"a".repeat(MAX_IDENT_LEN)).is_ok());
+        assert!(validate(&"a".repeat(MAX_IDENT_LEN + 1)).is_err());
+    }
+
+    #[test]
+    fn table_ref_display_test() {
+        assert_eq!(TableRef::new("cars").unwrap().to_string(), "\"cars\"");
+        assert_eq!(
+            TableRef::from_static(Some("billing"), "invoice").to_string(),
+            "\"billing\".\"invoice\""
+        );
+        assert!("billing.invoice.x".parse::<TableRef>().is_err());
+        assert_eq!(
+            TableRef::from_static(Some("billing"), "invoice").quoted(Dialect::MySql),
+            "`billing`.`invoice`"
+        );
+    }
+
+    #[test]
+    fn quote_test() {
+        assert_eq!(quote("user", Dialect::Postgres), "\"user\"");
+        assert_eq!(quote("a`b", Dialect::MySql), "`a``b`");
+        assert_eq!(quote("a`b", Dialect::Sqlite), "\"a`b\"");
+    }
+}
@@ -0,0 +1,201 @@
+//! Keyset pagination of `PgInsert` structs: `Car::page_after(cursor, limit, direction, pool)`
+//!
+//! Rows are ordered by the cursor columns ( `#[sqlxinsert(cursor = "created_at, id")]`,
+//! the `update(by)` columns by default ) and the next page starts after the last row,
+//! `where ("created_at", "id") > ($1::timestamptz, $2::integer)`. The cursor columns
+//! must be unique together and not null.
+use std::fmt;
+use std::str::FromStr;
+
+use crate::TableRef;
+
+/// Order of the cursor columns
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Asc,
+    Desc,
+}
+
+/// Position after the last row of a page, an opaque URL safe token
+///
+/// The `Display` form is the wire format, `FromStr` reads it back. With feature `serde`
+/// the cursor serializes as the same string.
+///
+/// ```rust
+/// use sqlxinsert::page::Cursor;
+///
+/// let cursor = Cursor::from_values(vec!["2026-10-19 12:00:00+00".into(), "42".into()]);
+/// let token = cursor.to_string();
+/// assert_eq!(token.parse::<Cursor>().unwrap(), cursor);
+/// assert!("not a cursor".parse::<Cursor>().is_err());
+///
+/// # #[cfg(feature = "serde")]
+/// # {
+/// let json = serde_json::to_string(&cursor).unwrap();
+/// assert_eq!(json, format!("\"{}\"", token));
+/// assert_eq!(serde_json::from_str::<Cursor>(&json).unwrap(), cursor);
+/// assert!(serde_json::from_str::<Cursor>("\"zz\"").is_err());
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Cursor {
+    /// Text form of the cursor columns of the last row
+    values: Vec<String>,
+}
+
+impl Cursor {
+    #[doc(hidden)]
+    pub fn from_values(values: Vec<String>) -> Self {
+        Cursor { values }
+    }
+
+    /// Values of `columns` cursor columns, error for a cursor of another struct
+    #[doc(hidden)]
+    pub fn values(&self, columns: usize) -> eyre::Result<&[String]> {
+        if self.values.len() != columns {
+            eyre::bail!(
+                "cursor has {} values, expected {}",
+                self.values.len(),
+                columns
+            );
+        }
+        Ok(&self.values)
+    }
+}
+
+/// Hex of the values separated by `\0`, which Postgres text cannot contain
+impl fmt::Display for Cursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, value) in self.values.iter().enumerate() {
+            if i > 0 {
+                f.write_str("00")?;
+            }
+            for byte in value.bytes() {
+                write!(f, "{:02x}", byte)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Cursor {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if !s.len().is_multiple_of(2) || !s.bytes().all(|b| b.is_ascii_hexdigit()) {
+            eyre::bail!("invalid cursor `{}`", s);
+        }
+        let bytes = (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16))
+            .collect::<Result<Vec<_>, _>>()?;
+        let values = bytes
+            .split(|byte| *byte == 0)
+            .map(|value| String::from_utf8(value.to_vec()))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Cursor { values })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Cursor {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Cursor {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let token = String::deserialize(deserializer)?;
+        token.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Rows of one page and the cursor of the next one, `None` on the last page
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next: Option<Cursor>,
+}
+
+impl<T> Page<T> {
+    /// Page of `limit` rows out of `rows` fetched with `limit + 1`,
+    /// the extra row only tells there is a next page
+    #[doc(hidden)]
+    pub fn from_rows(rows: Vec<(T, Vec<String>)>, limit: i64) -> Self {
+        let more = rows.len() as i64 > limit;
+        let mut items = Vec::with_capacity(rows.len());
+        let mut last = None;
+        for (item, values) in rows.into_iter().take(limit as usize) {
+            items.push(item);
+            last = Some(values);
+        }
+        Page {
+            items,
+            next: last.filter(|_| more).map(Cursor::from_values),
+        }
+    }
+}
+
+/// Column alias of the cursor values in the page query
+pub const CURSOR_COLUMN: &str = "sqlxinsert_cursor";
+
+/// Page query of `table` ordered by `columns` ( quoted column and its type ),
+/// `$1..$n` are the cursor values when `after` and the last placeholder is the limit
+///
+/// ```rust
+/// use sqlxinsert::page::{select_sql, Direction};
+///
+/// let table = sqlxinsert::TableRef::new("cars").unwrap();
+/// let columns = [(r#""created_at""#, "timestamptz"), (r#""id""#, "integer")];
+/// assert_eq!(
+///     select_sql(&table, &columns, Direction::Desc, true),
+///     r#"select *, array["created_at"::text, "id"::text] as "sqlxinsert_cursor" from "cars" where ("created_at", "id") < ($1::timestamptz, $2::integer) order by "created_at" desc, "id" desc limit $3"#
+/// );
+/// ```
+pub fn select_sql(
+    table: &TableRef,
+    columns: &[(&str, &str)],
+    direction: Direction,
+    after: bool,
+) -> String {
+    let (op, order) = match direction {
+        Direction::Asc => (">", "asc"),
+        Direction::Desc => ("<", "desc"),
+    };
+    let texts = columns
+        .iter()
+        .map(|(column, _)| format!("{}::text", column))
+        .collect::<Vec<_>>();
+    let names = columns
+        .iter()
+        .map(|(column, _)| *column)
+        .collect::<Vec<_>>();
+    let mut sql = format!(
+        "select *, array[{}] as \"{}\" from {}",
+        texts.join(", "),
+        CURSOR_COLUMN,
+        table
+    );
+    if after {
+        let values = columns
+            .iter()
+            .enumerate()
+            .map(|(i, (_, sql_type))| format!("${}::{}", i + 1, sql_type))
+            .collect::<Vec<_>>();
+        sql.push_str(&format!(
+            " where ({}) {} ({})",
+            names.join(", "),
+            op,
+            values.join(", ")
+        ));
+    }
+    let order = names
+        .iter()
+        .map(|column| format!("{} {}", column, order))
+        .collect::<Vec<_>>();
+    let limit = if after { columns.len() + 1 } else { 1 };
+    sql.push_str(&format!(" order by {} limit ${}", order.join(", "), limit));
+    sql
+}
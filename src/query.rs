@@ -0,0 +1,568 @@
+//! Typed `where` clauses for `select`, `update` and `delete` ( feature `query` )
+//!
+//! `PgInsert` generates a `Column` per field in `Car::col`, `Car::col.color` is a
+//! `Column<Car, String>`, so filters only accept columns of the queried struct
+//! and values of the field type. Values are bound by `sqlx::QueryBuilder`.
+//!
+//! ```rust,ignore
+//! let cars = Car::query()
+//!     .filter(Car::col.color.eq("red"))
+//!     .filter(Car::col.id.gt(10).or(Car::col.name.like("S%")))
+//!     .order_by(Car::col.name.asc())
+//!     .limit(50)
+//!     .fetch_all(&pool)
+//!     .await?;
+//! ```
+use std::fmt;
+use std::marker::PhantomData;
+
+use sqlx::postgres::{PgQueryResult, PgRow};
+use sqlx::{Encode, Postgres, QueryBuilder, Transaction, Type};
+
+use crate::TableRef;
+
+type Builder = QueryBuilder<'static, Postgres>;
+
+/// Value bound into the builder
+type Bind = Box<dyn FnOnce(&mut Builder) + Send>;
+
+/// Values usable in filters
+pub trait Value: for<'q> Encode<'q, Postgres> + Type<Postgres> + Send + 'static {}
+
+impl<V> Value for V where V: for<'q> Encode<'q, Postgres> + Type<Postgres> + Send + 'static {}
+
+/// Argument of filters on a column of type `V`: `V` itself, or `&str` for `String` columns
+///
+/// Unlike `Into`, integer literals still infer the column type ( `Car::col.wheels.eq(4)` ).
+pub trait IntoValue<V> {
+    fn into_value(self) -> V;
+}
+
+impl<V: Value> IntoValue<V> for V {
+    fn into_value(self) -> V {
+        self
+    }
+}
+
+impl IntoValue<String> for &str {
+    fn into_value(self) -> String {
+        self.to_string()
+    }
+}
+
+/// Column of struct `T` holding values of type `V` ( `Option` removed )
+pub struct Column<T, V> {
+    name: &'static str,
+    /// Cast of the placeholder: `cast` or `pg_type` of the field
+    cast: Option<&'static str>,
+    marker: PhantomData<fn() -> (T, V)>,
+}
+
+impl<T, V> Clone for Column<T, V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, V> Copy for Column<T, V> {}
+
+impl<T, V> fmt::Debug for Column<T, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Column").field(&self.name).finish()
+    }
+}
+
+impl<T, V> Column<T, V> {
+    #[doc(hidden)]
+    pub const fn new(name: &'static str, cast: Option<&'static str>) -> Self {
+        Column {
+            name,
+            cast,
+            marker: PhantomData,
+        }
+    }
+
+    /// Unquoted column name
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn is_null(self) -> Filter<T> {
+        Filter::new(vec![Part::Sql(format!("{} is null", self.quoted()))])
+    }
+
+    pub fn is_not_null(self) -> Filter<T> {
+        Filter::new(vec![Part::Sql(format!("{} is not null", self.quoted()))])
+    }
+
+    pub fn asc(self) -> Order<T> {
+        Order::new(format!("{} asc", self.quoted()))
+    }
+
+    pub fn desc(self) -> Order<T> {
+        Order::new(format!("{} desc", self.quoted()))
+    }
+
+    fn quoted(&self) -> String {
+        crate::ident::quote(self.name, crate::Dialect::Postgres)
+    }
+}
+
+impl<T, V: Value> Column<T, V> {
+    pub fn eq(self, value: impl IntoValue<V>) -> Filter<T> {
+        self.compare("=", value.into_value())
+    }
+
+    pub fn ne(self, value: impl IntoValue<V>) -> Filter<T> {
+        self.compare("<>", value.into_value())
+    }
+
+    pub fn gt(self, value: impl IntoValue<V>) -> Filter<T> {
+        self.compare(">", value.into_value())
+    }
+
+    pub fn ge(self, value: impl IntoValue<V>) -> Filter<T> {
+        self.compare(">=", value.into_value())
+    }
+
+    pub fn lt(self, value: impl IntoValue<V>) -> Filter<T> {
+        self.compare("<", value.into_value())
+    }
+
+    pub fn le(self, value: impl IntoValue<V>) -> Filter<T> {
+        self.compare("<=", value.into_value())
+    }
+
+    /// `"color" = any($1)`, needs `Vec<V>` to be a Postgres array
+    pub fn is_in(self, values: Vec<V>) -> Filter<T>
+    where
+        Vec<V>: Value,
+    {
+        let cast = self
+            .cast
+            .map_or_else(String::new, |cast| format!("::{}[]", cast));
+        Filter::new(bound(
+            format!("{} = any(", self.quoted()),
+            values,
+            cast + ")",
+        ))
+    }
+
+    /// Assignment of `Query::update`
+    pub fn set(self, value: impl IntoValue<V>) -> Set<T> {
+        Set {
+            parts: bound(
+                format!("{} = ", self.quoted()),
+                value.into_value(),
+                self.cast_suffix(),
+            ),
+            marker: PhantomData,
+        }
+    }
+
+    fn compare(self, op: &str, value: V) -> Filter<T> {
+        Filter::new(bound(
+            format!("{} {} ", self.quoted(), op),
+            value,
+            self.cast_suffix(),
+        ))
+    }
+
+    fn cast_suffix(&self) -> String {
+        self.cast
+            .map_or_else(String::new, |cast| format!("::{}", cast))
+    }
+}
+
+impl<T> Column<T, String> {
+    /// `"name" like $1`
+    pub fn like(self, pattern: impl IntoValue<String>) -> Filter<T> {
+        self.compare("like", pattern.into_value())
+    }
+
+    /// `"name" ilike $1`, case insensitive
+    pub fn ilike(self, pattern: impl IntoValue<String>) -> Filter<T> {
+        self.compare("ilike", pattern.into_value())
+    }
+}
+
+enum Part {
+    Sql(String),
+    Bind(Bind),
+}
+
+/// `{before}$1{after}`
+fn bound<V: Value>(before: String, value: V, after: String) -> Vec<Part> {
+    let mut parts = vec![
+        Part::Sql(before),
+        Part::Bind(Box::new(move |builder: &mut Builder| {
+            builder.push_bind(value);
+        })),
+    ];
+    if !after.is_empty() {
+        parts.push(Part::Sql(after));
+    }
+    parts
+}
+
+fn push_parts(builder: &mut Builder, parts: Vec<Part>) {
+    for part in parts {
+        match part {
+            Part::Sql(sql) => {
+                builder.push(sql);
+            }
+            Part::Bind(bind) => bind(builder),
+        }
+    }
+}
+
+/// Condition on columns of `T`
+pub struct Filter<T> {
+    parts: Vec<Part>,
+    /// Joined by `and` or `or`, parenthesized when combined again
+    compound: bool,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Filter<T> {
+    fn new(parts: Vec<Part>) -> Self {
+        Filter {
+            parts,
+            compound: false,
+            marker: PhantomData,
+        }
+    }
+
+    /// Matches every row, `where true`: `Job::lock_next(n, Filter::all(), lock, &mut tx)`
+    pub fn all() -> Filter<T> {
+        Filter::new(vec![Part::Sql("true".to_string())])
+    }
+
+    pub fn and(self, other: Filter<T>) -> Filter<T> {
+        self.join(" and ", other)
+    }
+
+    pub fn or(self, other: Filter<T>) -> Filter<T> {
+        self.join(" or ", other)
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(self) -> Filter<T> {
+        let mut parts = vec![Part::Sql("not ".to_string())];
+        parts.extend(self.parenthesized());
+        Filter::new(parts)
+    }
+
+    fn join(self, op: &str, other: Filter<T>) -> Filter<T> {
+        let mut parts = self.parenthesized();
+        parts.push(Part::Sql(op.to_string()));
+        parts.extend(other.parenthesized());
+        Filter {
+            parts,
+            compound: true,
+            marker: PhantomData,
+        }
+    }
+
+    /// Parts, in parentheses when compound
+    fn parenthesized(self) -> Vec<Part> {
+        if !self.compound {
+            return self.parts;
+        }
+        let mut parts = vec![Part::Sql("(".to_string())];
+        parts.extend(self.parts);
+        parts.push(Part::Sql(")".to_string()));
+        parts
+    }
+}
+
+/// `"name" asc` of `order by`
+pub struct Order<T> {
+    sql: String,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Order<T> {
+    fn new(sql: String) -> Self {
+        Order {
+            sql,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn nulls_first(mut self) -> Self {
+        self.sql.push_str(" nulls first");
+        self
+    }
+
+    pub fn nulls_last(mut self) -> Self {
+        self.sql.push_str(" nulls last");
+        self
+    }
+}
+
+/// Row lock of a `select`: `for update skip locked`
+///
+/// ```rust
+/// use sqlxinsert::query::Lock;
+///
+/// assert_eq!(Lock::update().skip_locked().to_string(), "for update skip locked");
+/// assert_eq!(Lock::no_key_update().nowait().to_string(), "for no key update nowait");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Lock {
+    strength: &'static str,
+    wait: &'static str,
+}
+
+impl Lock {
+    /// `for update`, blocks other updates, deletes and locks of the rows
+    pub const fn update() -> Self {
+        Lock {
+            strength: "for update",
+            wait: "",
+        }
+    }
+
+    /// `for no key update`, weaker than `update`, foreign keys to the rows still work
+    pub const fn no_key_update() -> Self {
+        Lock {
+            strength: "for no key update",
+            wait: "",
+        }
+    }
+
+    /// Skip rows locked by others instead of waiting, the usual work queue lock
+    pub const fn skip_locked(mut self) -> Self {
+        self.wait = " skip locked";
+        self
+    }
+
+    /// Fail instead of waiting for rows locked by others
+    pub const fn nowait(mut self) -> Self {
+        self.wait = " nowait";
+        self
+    }
+}
+
+impl fmt::Display for Lock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.strength, self.wait)
+    }
+}
+
+/// `"color" = $1` of `update set`
+pub struct Set<T> {
+    parts: Vec<Part>,
+    marker: PhantomData<fn() -> T>,
+}
+
+/// `select`, `update` or `delete` of rows of `T` matching all filters
+pub struct Query<T> {
+    table: TableRef,
+    filters: Vec<Filter<T>>,
+    order: Vec<Order<T>>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+    lock: Option<Lock>,
+}
+
+impl<T> Query<T> {
+    /// Query of `table`, generated as `Car::query()` and `Car::query_in(table)`
+    pub fn new(table: TableRef) -> Self {
+        Query {
+            table,
+            filters: Vec::new(),
+            order: Vec::new(),
+            limit: None,
+            offset: None,
+            lock: None,
+        }
+    }
+
+    /// Add a condition, all conditions must hold
+    pub fn filter(mut self, filter: Filter<T>) -> Self {
+        self.filters.push(filter);
+        self
+    }
+
+    pub fn order_by(mut self, order: Order<T>) -> Self {
+        self.order.push(order);
+        self
+    }
+
+    pub fn limit(mut self, limit: i64) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn offset(mut self, offset: i64) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Lock the selected rows, fetch them with `fetch_locked` inside a transaction
+    pub fn lock(mut self, lock: Lock) -> Self {
+        self.lock = Some(lock);
+        self
+    }
+
+    /// `order by` of already quoted columns, generated `lock_next` orders by the key
+    #[doc(hidden)]
+    pub fn order_by_sql(mut self, sql: &str) -> Self {
+        self.order.push(Order::new(sql.to_string()));
+        self
+    }
+
+    /// `select * from ...` with bound values, `sql()` of the builder shows the statement
+    pub fn select_builder(self) -> QueryBuilder<'static, Postgres> {
+        let mut builder = QueryBuilder::new(format!("select * from {}", self.table));
+        let Query {
+            filters,
+            order,
+            limit,
+            offset,
+            lock,
+            ..
+        } = self;
+        push_where(&mut builder, filters);
+        if !order.is_empty() {
+            let order = order.into_iter().map(|order| order.sql).collect::<Vec<_>>();
+            builder.push(format!(" order by {}", order.join(", ")));
+        }
+        if let Some(limit) = limit {
+            builder.push(" limit ").push_bind(limit);
+        }
+        if let Some(offset) = offset {
+            builder.push(" offset ").push_bind(offset);
+        }
+        if let Some(lock) = lock {
+            builder.push(format!(" {}", lock));
+        }
+        builder
+    }
+
+    /// `select count(*) from ... where ...`, ordering, limit and offset are ignored
+    pub fn count_builder(self) -> QueryBuilder<'static, Postgres> {
+        let mut builder = QueryBuilder::new(format!("select count(*) from {}", self.table));
+        push_where(&mut builder, self.filters);
+        builder
+    }
+
+    /// `update ... set ... where ...`, ordering, limit and lock are ignored
+    pub fn update_builder(self, set: Vec<Set<T>>) -> eyre::Result<QueryBuilder<'static, Postgres>> {
+        if set.is_empty() {
+            eyre::bail!("update of {} without assignments", self.table);
+        }
+        let mut builder = QueryBuilder::new(format!("update {} set ", self.table));
+        for (i, set) in set.into_iter().enumerate() {
+            if i > 0 {
+                builder.push(", ");
+            }
+            push_parts(&mut builder, set.parts);
+        }
+        push_where(&mut builder, self.filters);
+        Ok(builder)
+    }
+
+    /// `delete from ... where ...`, ordering, limit and lock are ignored
+    pub fn delete_builder(self) -> QueryBuilder<'static, Postgres> {
+        let mut builder = QueryBuilder::new(format!("delete from {}", self.table));
+        push_where(&mut builder, self.filters);
+        builder
+    }
+
+    pub async fn fetch_all<'e, E>(self, executor: E) -> eyre::Result<Vec<T>>
+    where
+        E: sqlx::Executor<'e, Database = Postgres>,
+        T: for<'r> sqlx::FromRow<'r, PgRow> + Send + Unpin,
+    {
+        let mut builder = self.select_builder();
+        Ok(builder.build_query_as::<T>().fetch_all(executor).await?)
+    }
+
+    /// First row, `None` when no row matches
+    pub async fn fetch_optional<'e, E>(self, executor: E) -> eyre::Result<Option<T>>
+    where
+        E: sqlx::Executor<'e, Database = Postgres>,
+        T: for<'r> sqlx::FromRow<'r, PgRow> + Send + Unpin,
+    {
+        let mut builder = self.select_builder();
+        Ok(builder
+            .build_query_as::<T>()
+            .fetch_optional(executor)
+            .await?)
+    }
+
+    /// First row, error when no row matches
+    pub async fn fetch_one<'e, E>(self, executor: E) -> eyre::Result<T>
+    where
+        E: sqlx::Executor<'e, Database = Postgres>,
+        T: for<'r> sqlx::FromRow<'r, PgRow> + Send + Unpin,
+    {
+        let mut builder = self.select_builder();
+        Ok(builder.build_query_as::<T>().fetch_one(executor).await?)
+    }
+
+    /// Number of matching rows
+    pub async fn count<'e, E>(self, executor: E) -> eyre::Result<i64>
+    where
+        E: sqlx::Executor<'e, Database = Postgres>,
+    {
+        let mut builder = self.count_builder();
+        let (count,) = builder
+            .build_query_as::<(i64,)>()
+            .fetch_one(executor)
+            .await?;
+        Ok(count)
+    }
+
+    /// Matching rows locked until `tx` commits or rolls back, a lock outside of a
+    /// transaction would be released right after the `select`
+    pub async fn fetch_locked(self, tx: &mut Transaction<'_, Postgres>) -> eyre::Result<Vec<T>>
+    where
+        T: for<'r> sqlx::FromRow<'r, PgRow> + Send + Unpin,
+    {
+        self.fetch_locked_as::<T>(tx).await
+    }
+
+    /// Same as `fetch_locked` decoding rows as any `FromRow` type `O`
+    pub async fn fetch_locked_as<O>(
+        self,
+        tx: &mut Transaction<'_, Postgres>,
+    ) -> eyre::Result<Vec<O>>
+    where
+        O: for<'r> sqlx::FromRow<'r, PgRow> + Send + Unpin,
+    {
+        if self.lock.is_none() {
+            eyre::bail!("fetch_locked of {} without lock", self.table);
+        }
+        let mut builder = self.select_builder();
+        Ok(builder.build_query_as::<O>().fetch_all(&mut *tx).await?)
+    }
+
+    /// Update every matching row ( all rows without filters )
+    pub async fn update<'e, E>(self, set: Vec<Set<T>>, executor: E) -> eyre::Result<PgQueryResult>
+    where
+        E: sqlx::Executor<'e, Database = Postgres>,
+    {
+        let mut builder = self.update_builder(set)?;
+        Ok(builder.build().execute(executor).await?)
+    }
+
+    /// Delete every matching row ( all rows without filters )
+    pub async fn delete<'e, E>(self, executor: E) -> eyre::Result<PgQueryResult>
+    where
+        E: sqlx::Executor<'e, Database = Postgres>,
+    {
+        let mut builder = self.delete_builder();
+        Ok(builder.build().execute(executor).await?)
+    }
+}
+
+fn push_where<T>(builder: &mut Builder, filters: Vec<Filter<T>>) {
+    for (i, filter) in filters.into_iter().enumerate() {
+        builder.push(if i == 0 { " where " } else { " and " });
+        push_parts(builder, filter.parenthesized());
+    }
+}
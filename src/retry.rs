@@ -0,0 +1,123 @@
+//! Retries of serialization failures and deadlocks ( feature `retry` )
+//!
+//! Only errors Postgres reports as transient are retried, `40001` ( serialization
+//! failure ) and `40P01` ( deadlock detected ). Under `serializable` isolation the
+//! whole transaction has to run again, `RetryPolicy::tx` does that. Waits between
+//! attempts use `tokio::time`, a tokio based sqlx runtime is needed.
+//!
+//! ```rust,ignore
+//! use sqlxinsert::retry::{Attempts, RetryPolicy};
+//!
+//! let policy = RetryPolicy::new(5);
+//! let car = policy.run(|| car.upsert(&pool)).await?;
+//!
+//! let res = policy
+//!     .tx(&pool, |tx| Box::pin(async move { transfer(tx, 100).await }))
+//!     .await;
+//! if let Err(err) = &res {
+//!     let attempts = err.downcast_ref::<Attempts>().map(|a| a.attempts);
+//! }
+//! ```
+use std::fmt;
+use std::future::Future;
+use std::time::Duration;
+
+use sqlx::{PgPool, Postgres, Transaction};
+
+use crate::tx::TxFuture;
+
+/// SQLSTATE of errors worth running again
+const TRANSIENT: &[&str] = &["40001", "40P01"];
+
+/// How often and how patiently to retry, `RetryPolicy::new(5)` runs at most 5 times
+/// waiting 10ms, 20ms, 40ms, ... up to 1s between attempts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// At most `max_attempts` runs, including the first one. The first run always happens,
+    /// `0` behaves like `1`: no retries
+    pub const fn new(max_attempts: u32) -> Self {
+        RetryPolicy {
+            max_attempts,
+            backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_secs(1),
+        }
+    }
+
+    /// Wait before the first retry, doubled for every next one
+    pub const fn backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Longest wait between attempts
+    pub const fn max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// Wait after failed attempt `attempt` ( 1 based )
+    fn delay(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.backoff
+            .checked_mul(factor)
+            .map_or(self.max_backoff, |delay| delay.min(self.max_backoff))
+    }
+
+    /// Run `f` until it succeeds, fails with an error that is not transient or
+    /// `max_attempts` runs failed, errors carry `Attempts`
+    pub async fn run<R, F, Fut>(&self, mut f: F) -> eyre::Result<R>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = eyre::Result<R>>,
+    {
+        let mut attempt = 1;
+        loop {
+            match f().await {
+                Ok(value) => return Ok(value),
+                Err(err) if attempt < self.max_attempts && is_transient(&err) => {
+                    tokio::time::sleep(self.delay(attempt)).await;
+                    attempt += 1;
+                }
+                Err(err) => return Err(err.wrap_err(Attempts { attempts: attempt })),
+            }
+        }
+    }
+
+    /// `sqlxinsert::tx` run again from the start while it fails transiently
+    pub async fn tx<R, F>(&self, pool: &PgPool, f: F) -> eyre::Result<R>
+    where
+        F: for<'t> Fn(&'t mut Transaction<'_, Postgres>) -> TxFuture<'t, R>,
+    {
+        self.run(|| crate::tx::tx(pool, &f)).await
+    }
+}
+
+/// Serialization failure or deadlock anywhere in the chain of `err`
+pub fn is_transient(err: &eyre::Report) -> bool {
+    err.chain()
+        .filter_map(|cause| cause.downcast_ref::<sqlx::Error>())
+        .filter_map(|err| err.as_database_error()?.code())
+        .any(|code| TRANSIENT.contains(&code.as_ref()))
+}
+
+/// Context of errors returned by `RetryPolicy`, `err.downcast_ref::<Attempts>()`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Attempts {
+    /// Runs including the failed last one
+    pub attempts: u32,
+}
+
+impl fmt::Display for Attempts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.attempts {
+            1 => write!(f, "failed after 1 attempt"),
+            attempts => write!(f, "failed after {} attempts", attempts),
+        }
+    }
+}
@@ -0,0 +1,217 @@
+//! Compare `PgInsert` structs with the columns of their table in a live database.
+//!
+//! `verify` ( feature `verify` ) reads `information_schema.columns` of `T::table()`,
+//! `compare` works on columns from anywhere else, e.g. a dumped schema.
+use std::fmt;
+
+use crate::TableRef;
+
+/// Type name without aliases and modifiers, compared by `compare`, same rules as the
+/// derive's `check_schema`
+pub use sqlxinsert_schema::canonical_type;
+
+/// Column a struct writes, generated by `PgInsert`
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldColumn {
+    /// Unquoted column name
+    pub name: String,
+    /// Type of the field's cast or inferred from the field type, `None` when unknown
+    pub sql_type: Option<&'static str>,
+    /// Written by `insert` ( not in `insert(skip)` )
+    pub inserted: bool,
+}
+
+/// Column of the database table
+#[derive(Debug, Clone, PartialEq)]
+pub struct DbColumn {
+    pub name: String,
+    /// `udt_name` of `information_schema.columns`, arrays as `element[]`:
+    /// `int4`, `varchar`, `text[]`, `car_color`
+    pub sql_type: String,
+    pub nullable: bool,
+    /// Has a default, is an identity or generated column
+    pub has_default: bool,
+}
+
+/// Difference between a struct and its table
+#[derive(Debug, Clone, PartialEq)]
+pub enum Drift {
+    /// Table does not exist ( or has no columns visible to the user )
+    MissingTable(String),
+    /// Column the struct writes does not exist
+    MissingColumn(String),
+    /// `NOT NULL` column without default which `insert` does not write
+    NotNullOmitted(String),
+    TypeMismatch {
+        column: String,
+        expected: String,
+        actual: String,
+    },
+}
+
+impl fmt::Display for Drift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Drift::MissingTable(table) => write!(f, "table {} does not exist", table),
+            Drift::MissingColumn(column) => write!(f, "column `{}` does not exist", column),
+            Drift::NotNullOmitted(column) => write!(
+                f,
+                "column `{}` is NOT NULL without default but not inserted",
+                column
+            ),
+            Drift::TypeMismatch {
+                column,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "column `{}` is `{}`, expected `{}`",
+                column, actual, expected
+            ),
+        }
+    }
+}
+
+/// Table and written columns of a `PgInsert` struct
+pub trait Table {
+    /// Table used by `insert`, `upsert` and `update`
+    fn table() -> TableRef;
+
+    /// Columns written by `insert`, `upsert` and `update` in field order
+    fn field_columns() -> Vec<FieldColumn>;
+}
+
+/// Drift of `fields` against the table `columns`, empty when they match
+///
+/// ```rust
+/// use sqlxinsert::schema::{compare, DbColumn, Drift, FieldColumn};
+///
+/// let fields = vec![FieldColumn { name: "name".into(), sql_type: Some("text"), inserted: true }];
+/// let column = |name: &str, sql_type: &str, nullable| DbColumn {
+///     name: name.into(),
+///     sql_type: sql_type.into(),
+///     nullable,
+///     has_default: false,
+/// };
+/// assert!(compare(&fields, &[column("name", "varchar", false)]).is_empty());
+/// assert_eq!(
+///     compare(&fields, &[column("name", "int4", false), column("seats", "int2", false)]),
+///     vec![
+///         Drift::TypeMismatch { column: "name".into(), expected: "text".into(), actual: "int4".into() },
+///         Drift::NotNullOmitted("seats".into()),
+///     ]
+/// );
+/// ```
+pub fn compare(fields: &[FieldColumn], columns: &[DbColumn]) -> Vec<Drift> {
+    let mut drift = Vec::new();
+    for field in fields {
+        let column = match columns.iter().find(|column| column.name == field.name) {
+            Some(column) => column,
+            None => {
+                drift.push(Drift::MissingColumn(field.name.clone()));
+                continue;
+            }
+        };
+        if let Some(expected) = field.sql_type {
+            if canonical_type(expected) != canonical_type(&column.sql_type) {
+                drift.push(Drift::TypeMismatch {
+                    column: field.name.clone(),
+                    expected: expected.to_string(),
+                    actual: column.sql_type.clone(),
+                });
+            }
+        }
+    }
+    for column in columns {
+        let inserted = fields
+            .iter()
+            .any(|field| field.inserted && field.name == column.name);
+        if !column.nullable && !column.has_default && !inserted {
+            drift.push(Drift::NotNullOmitted(column.name.clone()));
+        }
+    }
+    drift
+}
+
+/// Columns of `table` from `information_schema.columns`,
+/// unqualified tables are looked up in `current_schema()`
+#[cfg(feature = "verify")]
+pub async fn columns<'e, E>(table: &TableRef, executor: E) -> eyre::Result<Vec<DbColumn>>
+where
+    E: sqlx::Executor<'e, Database = sqlx::Postgres>,
+{
+    // array udt_names are the element's prefixed with `_`, `data_type` tells them apart
+    let sql = "select column_name::text, \
+               case when data_type = 'ARRAY' then substr(udt_name, 2) || '[]' else udt_name end::text, \
+               is_nullable = 'YES', \
+               column_default is not null or is_identity = 'YES' or is_generated <> 'NEVER' \
+               from information_schema.columns \
+               where table_schema = coalesce($1, current_schema()) and table_name = $2 \
+               order by ordinal_position";
+    let rows: Vec<(String, String, bool, bool)> = sqlx::query_as(sql)
+        .bind(table.schema())
+        .bind(table.name())
+        .fetch_all(executor)
+        .await?;
+    Ok(rows
+        .into_iter()
+        .map(|(name, sql_type, nullable, has_default)| DbColumn {
+            name,
+            sql_type,
+            nullable,
+            has_default,
+        })
+        .collect())
+}
+
+/// Drift of `T` against its table in the database
+#[cfg(feature = "verify")]
+pub async fn drift<'e, T: Table>(
+    executor: impl sqlx::Executor<'e, Database = sqlx::Postgres>,
+) -> eyre::Result<Vec<Drift>> {
+    let table = T::table();
+    let columns = columns(&table, executor).await?;
+    if columns.is_empty() {
+        return Ok(vec![Drift::MissingTable(table.to_string())]);
+    }
+    Ok(compare(&T::field_columns(), &columns))
+}
+
+/// Fail when `T` does not match its table, for startup health checks
+///
+/// ```rust,ignore
+/// sqlxinsert::schema::verify::<Car>(&pool).await?;
+/// ```
+#[cfg(feature = "verify")]
+pub async fn verify<'e, T: Table>(
+    executor: impl sqlx::Executor<'e, Database = sqlx::Postgres>,
+) -> eyre::Result<()> {
+    let drift = drift::<T>(executor).await?;
+    if drift.is_empty() {
+        return Ok(());
+    }
+    let drift = drift.iter().map(Drift::to_string).collect::<Vec<_>>();
+    Err(eyre::eyre!(
+        "{} does not match {}: {}",
+        std::any::type_name::<T>(),
+        T::table(),
+        drift.join(", ")
+    ))
+}
+
+/// Test helper panicking with the drift of `T`
+///
+/// ```rust,ignore
+/// #[tokio::test]
+/// async fn car_matches_schema() {
+///     sqlxinsert::schema::assert_verified::<Car>(&pool).await;
+/// }
+/// ```
+#[cfg(feature = "verify")]
+pub async fn assert_verified<'e, T: Table>(
+    executor: impl sqlx::Executor<'e, Database = sqlx::Postgres>,
+) {
+    if let Err(e) = verify::<T>(executor).await {
+        panic!("{}", e);
+    }
+}
@@ -0,0 +1,124 @@
+//! Helpers for building the SQL text of generated queries.
+use std::borrow::Cow;
+
+use sqlxinsert_schema::replace_placeholder;
+
+/// Quoted column name and its value, `$` in the value is the placeholder:
+/// `("\"email\"", "lower($)")`
+pub type Column = (String, Cow<'static, str>);
+
+/// Number the placeholder of `value`, a standalone `$` outside of quoted text
+/// ( `sqlxinsert_schema::replace_placeholder` )
+///
+/// ```rust
+/// assert_eq!(sqlxinsert::sql::placeholder("lower($)", 3), "lower($3)");
+/// assert_eq!(sqlxinsert::sql::placeholder("$::jsonb", 1), "$1::jsonb");
+/// assert_eq!(sqlxinsert::sql::placeholder("'$' || $", 2), "'$' || $2");
+/// ```
+pub fn placeholder(value: &str, n: usize) -> String {
+    replace_placeholder(value, &format!("${}", n))
+}
+
+/// `"name", "color"`
+pub fn column_list(columns: &[Column]) -> String {
+    columns
+        .iter()
+        .map(|(column, _)| column.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Values of `columns` with placeholders numbered from `first`
+pub fn value_list(columns: &[Column], first: usize) -> Vec<String> {
+    columns
+        .iter()
+        .enumerate()
+        .map(|(i, (_, value))| placeholder(value, first + i))
+        .collect()
+}
+
+/// `"name"=$2,"color"=$3` ( `separator` is `" AND "` for `where` )
+///
+/// ```rust
+/// use sqlxinsert::sql::assignments;
+///
+/// let columns = vec![
+///     ("\"name\"".to_string(), "$".into()),
+///     ("\"email\"".to_string(), "lower($)".into()),
+/// ];
+/// assert_eq!(assignments(&columns, 2, ","), r#""name"=$2,"email"=lower($3)"#);
+/// ```
+pub fn assignments(columns: &[Column], first: usize, separator: &str) -> String {
+    columns
+        .iter()
+        .zip(value_list(columns, first))
+        .map(|((column, _), value)| format!("{}={}", column, value))
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+/// Columns of a flattened struct, `prefix` is prepended to every name
+///
+/// ```rust
+/// let columns = sqlxinsert::sql::flattened("address_", &["city", "zip"], &["$", "$"]);
+/// assert_eq!(columns.collect::<Vec<_>>()[1].0, r#""address_zip""#);
+/// ```
+pub fn flattened(
+    prefix: &'static str,
+    columns: &'static [&'static str],
+    values: &'static [&'static str],
+) -> impl Iterator<Item = Column> {
+    columns.iter().zip(values).map(move |(column, value)| {
+        let column = format!("{}{}", prefix, column);
+        (
+            crate::ident::quote(&column, crate::Dialect::Postgres),
+            Cow::Borrowed(*value),
+        )
+    })
+}
+
+/// Append `returning` clause, nothing for empty `returning`
+///
+/// ```rust
+/// use sqlxinsert::sql::with_returning;
+///
+/// let sql = "insert into \"cars\" ( \"name\" ) values ( $1 )".to_string();
+/// assert!(with_returning(sql.clone(), "\"id\"").ends_with(" returning \"id\""));
+/// assert_eq!(with_returning(sql.clone(), ""), sql);
+/// ```
+pub fn with_returning(mut sql: String, returning: &str) -> String {
+    if !returning.is_empty() {
+        sql.push_str(" returning ");
+        sql.push_str(returning);
+    }
+    sql
+}
+
+/// `$1,DEFAULT,$3::jsonb` values list
+///
+/// Value at `positions[bit]` is `DEFAULT` when `bit` is set in `defaults`.
+/// Other values keep their placeholders so binds do not depend on `defaults`.
+///
+/// ```rust
+/// use sqlxinsert::sql::values_with_defaults;
+///
+/// let values = ["$1", "$2", "$3::jsonb"];
+/// assert_eq!(values_with_defaults(&values, &[0, 2], 0), "$1,$2,$3::jsonb");
+/// assert_eq!(values_with_defaults(&values, &[0, 2], 0b10), "$1,$2,DEFAULT");
+/// assert_eq!(values_with_defaults(&values, &[0, 2], 0b11), "DEFAULT,$2,DEFAULT");
+/// ```
+pub fn values_with_defaults<S: AsRef<str>>(
+    values: &[S],
+    positions: &[usize],
+    defaults: u32,
+) -> String {
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| match positions.iter().position(|p| *p == i) {
+            Some(bit) if defaults & (1 << bit) != 0 => "DEFAULT",
+            _ => value.as_ref(),
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
@@ -0,0 +1,107 @@
+//! Rows of `PgInsert` structs one at a time ( feature `stream` )
+//!
+//! `Car::stream_all(&pool)` and `Car::stream_where(filter, &pool)` decode rows as the
+//! connection receives them. `Car::stream_cursor(batch_size, &mut tx)` declares a
+//! server-side cursor and fetches `batch_size` rows per round trip, which keeps memory
+//! bounded on both sides during long transactions.
+//!
+//! ```rust,ignore
+//! use futures::TryStreamExt;
+//!
+//! let mut cars = Car::stream_all(&pool);
+//! while let Some(car) = cars.try_next().await? {
+//!     export(car)?;
+//! }
+//! ```
+use std::sync::atomic::{AtomicU64, Ordering};
+
+pub use futures_core::stream::{BoxStream, Stream};
+use futures_util::TryStreamExt;
+use sqlx::postgres::{PgConnection, PgRow};
+use sqlx::{FromRow, Postgres};
+
+use crate::query::Query;
+
+/// Rows of `sql` decoded as `T`
+#[doc(hidden)]
+pub fn fetch<'e, T, E>(sql: String, executor: E) -> BoxStream<'e, eyre::Result<T>>
+where
+    E: sqlx::Executor<'e, Database = Postgres> + 'e,
+    T: for<'r> FromRow<'r, PgRow> + Send + Unpin + 'e,
+{
+    Box::pin(async_stream::try_stream! {
+        let mut rows = sqlx::query_as::<_, T>(&sql).fetch(executor);
+        while let Some(row) = rows.try_next().await? {
+            yield row;
+        }
+    })
+}
+
+/// Server-side cursors are named `sqlxinsert_stream_<n>`, unique within the process
+static NEXT_CURSOR: AtomicU64 = AtomicU64::new(0);
+
+/// Rows of `sql` decoded as `T`, fetched `batch_size` at a time from a cursor
+///
+/// Postgres only declares cursors inside a transaction, outside of one the stream
+/// yields that error. A stream dropped early leaves its cursor open until the
+/// transaction ends.
+#[doc(hidden)]
+pub fn cursor<'c, T>(
+    sql: String,
+    batch_size: i64,
+    conn: &'c mut PgConnection,
+) -> BoxStream<'c, eyre::Result<T>>
+where
+    T: for<'r> FromRow<'r, PgRow> + Send + Unpin + 'c,
+{
+    Box::pin(async_stream::try_stream! {
+        if batch_size < 1 {
+            Err(eyre::eyre!("cursor batch size must be positive, got {}", batch_size))?;
+        }
+        let name = format!("sqlxinsert_stream_{}", NEXT_CURSOR.fetch_add(1, Ordering::Relaxed));
+        let declare = format!("declare \"{}\" no scroll cursor for {}", name, sql);
+        sqlx::query(&declare).execute(&mut *conn).await?;
+
+        let fetch = format!("fetch forward {} from \"{}\"", batch_size, name);
+        loop {
+            let rows = sqlx::query_as::<_, T>(&fetch).fetch_all(&mut *conn).await?;
+            let last = (rows.len() as i64) < batch_size;
+            for row in rows {
+                yield row;
+            }
+            if last {
+                break;
+            }
+        }
+        sqlx::query(&format!("close \"{}\"", name))
+            .execute(&mut *conn)
+            .await?;
+    })
+}
+
+impl<T> Query<T> {
+    /// Matching rows one at a time, decoded as `T`
+    pub fn stream<'e, E>(self, executor: E) -> BoxStream<'e, eyre::Result<T>>
+    where
+        E: sqlx::Executor<'e, Database = Postgres> + 'e,
+        T: for<'r> FromRow<'r, PgRow> + Send + Unpin + 'e,
+    {
+        self.stream_as(executor)
+    }
+
+    /// Matching rows one at a time, decoded as any `FromRow` type `O`
+    pub fn stream_as<'e, O, E>(self, executor: E) -> BoxStream<'e, eyre::Result<O>>
+    where
+        E: sqlx::Executor<'e, Database = Postgres> + 'e,
+        O: for<'r> FromRow<'r, PgRow> + Send + Unpin + 'e,
+        T: 'e,
+    {
+        Box::pin(async_stream::try_stream! {
+            let mut builder = self.select_builder();
+            let mut rows = builder.build_query_as::<O>().fetch(executor);
+            while let Some(row) = rows.try_next().await? {
+                yield row;
+            }
+        })
+    }
+}
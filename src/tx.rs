@@ -0,0 +1,79 @@
+//! Transactions committed or rolled back by the result of a closure ( feature `tx` )
+//!
+//! ```rust,ignore
+//! let car = sqlxinsert::tx(&pool, |tx| {
+//!     Box::pin(async move {
+//!         let car = car.insert(&mut *tx).await?;
+//!         Wheel::insert_all_in_tx(&wheels, tx).await?;
+//!         Ok(car)
+//!     })
+//! })
+//! .await?;
+//! ```
+//!
+//! Like `sqlx::Connection::transaction` the closure returns a boxed future borrowing
+//! the transaction, values used inside are moved into it.
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+
+use sqlx::{Acquire, PgPool, Postgres, Transaction};
+
+/// Future of a transaction closure borrowing the transaction for `'t`
+pub type TxFuture<'t, R> = Pin<Box<dyn Future<Output = eyre::Result<R>> + Send + 't>>;
+
+/// Run `f` in a transaction of `pool`, committed when `f` returns `Ok` and
+/// rolled back when it returns `Err`
+pub async fn tx<R, F>(pool: &PgPool, f: F) -> eyre::Result<R>
+where
+    F: for<'t> FnOnce(&'t mut Transaction<'_, Postgres>) -> TxFuture<'t, R>,
+{
+    let tx = pool.begin().await?;
+    finish(tx, f).await
+}
+
+/// Run `f` in a savepoint of `tx`, released when `f` returns `Ok` and rolled back
+/// when it returns `Err`, `tx` itself stays usable either way
+pub async fn savepoint<R, F>(tx: &mut Transaction<'_, Postgres>, f: F) -> eyre::Result<R>
+where
+    F: for<'t> FnOnce(&'t mut Transaction<'_, Postgres>) -> TxFuture<'t, R>,
+{
+    let savepoint = tx.begin().await?;
+    finish(savepoint, f).await
+}
+
+async fn finish<R, F>(mut tx: Transaction<'_, Postgres>, f: F) -> eyre::Result<R>
+where
+    F: for<'t> FnOnce(&'t mut Transaction<'_, Postgres>) -> TxFuture<'t, R>,
+{
+    match f(&mut tx).await {
+        Ok(value) => {
+            tx.commit().await?;
+            Ok(value)
+        }
+        Err(err) => {
+            // the error of `f` matters more than a failed rollback
+            let _ = tx.rollback().await;
+            Err(err)
+        }
+    }
+}
+
+/// Context of the error of `insert_all_in_tx`, `err.downcast_ref::<ElementError>()`
+/// tells which element failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElementError {
+    /// Index of the element in the inserted slice
+    pub index: usize,
+    pub table: String,
+}
+
+impl fmt::Display for ElementError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "insert of element {} into {} failed",
+            self.index, self.table
+        )
+    }
+}
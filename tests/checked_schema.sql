@@ -0,0 +1,3 @@
+-- Tables `sqlx::query!` checks `#[sqlxinsert(checked)]` structs against when compiling
+-- `psql_checked_test`, created before building it with feature `checked`
+CREATE TABLE IF NOT EXISTS checked_cars ( id SERIAL PRIMARY KEY, name TEXT NOT NULL UNIQUE, color TEXT, spec JSONB NOT NULL );
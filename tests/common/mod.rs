@@ -0,0 +1,22 @@
+//! Golden file comparison shared by the test crates of the workspace
+//! ( `sqlxinsert-cli` includes this file with `#[path]` )
+use std::path::PathBuf;
+
+/// `parts` joined to the manifest dir of the crate under test
+pub fn path(parts: &[&str]) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.extend(parts);
+    path
+}
+
+/// Compare `actual` with `tests/golden/<name>`, `UPDATE_GOLDEN=1` rewrites the file instead
+pub fn assert_golden(name: &str, actual: &str) {
+    let path = path(&["tests", "golden", name]);
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(&path, actual).expect("Not possible to write golden file");
+        return;
+    }
+    let expected = std::fs::read_to_string(&path)
+        .unwrap_or_else(|_| panic!("Missing {}, run with UPDATE_GOLDEN=1", path.display()));
+    assert_eq!(actual, expected, "output differs from {}", path.display());
+}
@@ -0,0 +1,11 @@
+-- insert
+insert into "car" ( "name", "color" ) values ( $1,$2 ) returning *;
+
+-- upsert
+insert into "car" ( "name", "color" ) values ( $1,$2 ) on conflict ("id") do update set "name"=$3,"color"=$4 returning *;
+
+-- update
+update "car" set "name"=$1,"color"=$2 where "id"=$3 returning *;
+
+-- delete
+delete from "car" where "id"=$1 returning *;
@@ -0,0 +1,11 @@
+-- insert
+insert into "cars" ( "name", "color", "wheels" ) values ( $1,$2,$3 ) returning *;
+
+-- upsert
+insert into "cars" ( "name", "color", "wheels" ) values ( $1,$2,$3 ) on conflict ("name", "color") do update set "wheels"=$4 returning *;
+
+-- update
+update "cars" set "wheels"=$1 where "name"=$2 AND "id"=$3 AND "color"=$4 returning *;
+
+-- delete
+delete from "cars" where "name"=$1 AND "id"=$2 AND "color"=$3 returning *;
@@ -0,0 +1,14 @@
+-- insert
+insert into "customers" ( "name", "street", "city", "billing_street", "billing_city", "tier" ) values ( $1,$2,upper($3),$4,upper($5),$6 ) returning *;
+
+-- insert default tier
+insert into "customers" ( "name", "street", "city", "billing_street", "billing_city", "tier" ) values ( $1,$2,upper($3),$4,upper($5),DEFAULT ) returning *;
+
+-- upsert
+insert into "customers" ( "name", "street", "city", "billing_street", "billing_city", "tier" ) values ( $1,$2,upper($3),$4,upper($5),$6 ) on conflict ("name") do update set "name"=$7,"street"=$8,"city"=$9,"billing_street"=$10,"billing_city"=$11,"tier"=$12 returning *;
+
+-- update
+update "customers" set "name"=$1,"street"=$2,"city"=$3,"billing_street"=$4,"billing_city"=$5,"tier"=$6 where "id"=$7 returning *;
+
+-- delete
+delete from "customers" where "id"=$1 returning *;
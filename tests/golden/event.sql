@@ -0,0 +1,23 @@
+-- insert
+insert into "events" ( "name", "created_at" ) values ( $1,$2 ) returning "id", "created_at";
+
+-- upsert
+insert into "events" ( "name", "created_at" ) values ( $1,$2 ) on conflict ("id") do update set "name"=$3,"created_at"=$4 returning "id", "created_at";
+
+-- update
+update "events" set "name"=$1,"created_at"=$2 where "id"=$3 returning "id", "created_at";
+
+-- delete
+delete from "events" where "id"=$1 returning "id", "created_at";
+
+-- insert_returning_key
+insert into "events" ( "name", "created_at" ) values ( $1,$2 ) returning "id";
+
+-- execute_insert
+insert into "events" ( "name", "created_at" ) values ( $1,$2 );
+
+-- execute_upsert
+insert into "events" ( "name", "created_at" ) values ( $1,$2 ) on conflict ("id") do update set "name"=$3,"created_at"=$4;
+
+-- execute_update
+update "events" set "name"=$1,"created_at"=$2 where "id"=$3;
@@ -0,0 +1,14 @@
+-- insert
+insert into "hosts" ( "address", "email", "tags" ) values ( $1::inet,lower($2),$3::jsonb ) returning *;
+
+-- insert default tags
+insert into "hosts" ( "address", "email", "tags" ) values ( $1::inet,lower($2),DEFAULT ) returning *;
+
+-- upsert
+insert into "hosts" ( "address", "email", "tags" ) values ( $1::inet,lower($2),$3::jsonb ) on conflict ("address") do update set "email"=lower(trim($4)),"tags"=$5::jsonb returning *;
+
+-- update
+update "hosts" set "email"=lower(trim($1)),"tags"=$2::jsonb where "address"=$3::inet returning *;
+
+-- delete
+delete from "hosts" where "address"=$1::inet returning *;
@@ -0,0 +1,11 @@
+-- insert
+insert into "billing"."invoice" ( "amount" ) values ( $1 ) returning *;
+
+-- upsert
+insert into "billing"."invoice" ( "amount" ) values ( $1 ) on conflict ("id") do update set "amount"=$2,"created_at"=$3 returning *;
+
+-- update
+update "billing"."invoice" set "amount"=$1,"created_at"=$2 where "id"=$3 returning *;
+
+-- delete
+delete from "billing"."invoice" where "id"=$1 returning *;
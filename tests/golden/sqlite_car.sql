@@ -0,0 +1,5 @@
+-- create
+create table "cars" ( "id" integer primary key, "name" text not null );
+
+-- insert
+insert into "cars" ( "id", "name" ) values ( $1,$2 );
@@ -0,0 +1,5 @@
+-- create
+create table "categories" ( "code" text not null, "label" text, primary key ("code") );
+
+-- insert
+insert into "categories" ( "code", "label" ) values ( $1,$2 );
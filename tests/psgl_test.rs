@@ -13,6 +13,7 @@ struct Car {
 }
 
 //for<'c> sqlx::FromRow<'c, sqlx::postgres::PgRow> +
+#[allow(dead_code)]
 #[async_trait]
 trait DBOps: Send +  Unpin + Sized {
     const TABLE_NAME: &'static str;
@@ -21,7 +22,7 @@ trait DBOps: Send +  Unpin + Sized {
     where
         E: sqlx::Executor<'e, Database = sqlx::Postgres>;
 
-    async fn update<'e, E>(&self, pool: E) -> eyre::Result<Self>
+    async fn update<'e, E>(&self, pool: E) -> eyre::Result<Option<Self>>
     where
         E: sqlx::Executor<'e, Database = sqlx::Postgres>;
 
@@ -31,8 +32,9 @@ trait DBOps: Send +  Unpin + Sized {
 }
 
 
+#[allow(dead_code)]
 #[derive(Default, Debug, sqlx::FromRow, sqlxinsert::PgInsert)]
-#[sqlxinsert(table="car", update(by="name,id"), conflict="name")]
+#[sqlxinsert(table="cars", update(by="name,id"), conflict="name")]
 struct CreateCar {
     pub id: Option<i32>,
     pub name: String,
@@ -40,7 +42,6 @@ struct CreateCar {
 }
 impl CreateCar {
     pub fn new<T: Into<String>>(name: T) -> Self {
-        format!("{}", 1);
         CreateCar {
             id: None,
             name: name.into(),
@@ -68,7 +69,7 @@ async fn test_macro_insert() {
 
     let create_table = "create table cars (
         id SERIAL PRIMARY KEY,
-        name TEXT NOT NULL,
+        name TEXT NOT NULL UNIQUE,
         color TEXT
     )";
 
@@ -81,15 +82,16 @@ async fn test_macro_insert() {
         .expect("Not possible to insert into dabase");
     assert_eq!(car_skoda_res.name, car_skoda.name);
 
-    let car_skoda_upsert = car_skoda.upsert(&pool).await.expect("");
-
     let car_tesla_res = car_tesla
         .insert(&pool)
         .await
         .expect("Not possible to insert into dabase");
     assert_eq!(car_tesla_res.name, car_tesla.name);
 
-    let cars = sqlx::query_as::<_, Car>("SELECT * FROM cars")
+    // on conflict still consumes a serial value so upsert goes after all inserts
+    let _car_skoda_upsert = car_skoda.upsert(&pool).await.expect("");
+
+    let cars = sqlx::query_as::<_, Car>("SELECT * FROM cars ORDER BY id")
         .fetch_all(&pool)
         .await
         .expect("Not possible to fetch");
@@ -0,0 +1,5 @@
+CREATE TABLE schema_cars (
+    id SERIAL PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    colour TEXT
+);
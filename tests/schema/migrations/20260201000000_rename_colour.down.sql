@@ -0,0 +1,2 @@
+ALTER TABLE schema_cars DROP COLUMN wheels;
+ALTER TABLE schema_cars RENAME COLUMN color TO colour;
@@ -0,0 +1,2 @@
+ALTER TABLE schema_cars RENAME COLUMN colour TO color;
+ALTER TABLE schema_cars ADD COLUMN wheels SMALLINT NOT NULL DEFAULT 4;
@@ -0,0 +1,16 @@
+-- Schema of tables used by `check_schema` tests
+CREATE TABLE schema_cars (
+    id SERIAL PRIMARY KEY,
+    name VARCHAR(40) NOT NULL UNIQUE,
+    color TEXT,
+    wheels SMALLINT NOT NULL DEFAULT 4,
+    tags TEXT[] NOT NULL DEFAULT '{}'
+);
+
+CREATE SCHEMA checked;
+
+CREATE TABLE checked.invoice (
+    id BIGSERIAL PRIMARY KEY,
+    amount DOUBLE PRECISION NOT NULL,
+    note TEXT
+);
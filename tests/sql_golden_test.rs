@@ -0,0 +1,247 @@
+//! Snapshot of the SQL generated for fixture structs
+//!
+//! Every operation is rendered into `tests/golden/<fixture>.sql` and compared
+//! with the checked in file. Run with `UPDATE_GOLDEN=1` to rewrite the files
+//! after an intended change and review the diff.
+// fixtures are only rendered, never read or written to a database
+#![allow(dead_code)]
+
+mod common;
+
+use async_trait::async_trait;
+
+#[async_trait]
+trait DBOps: Send + Unpin + Sized {
+    const TABLE_NAME: &'static str;
+
+    async fn insert<'e, E>(&self, pool: E) -> eyre::Result<Self>
+    where
+        E: sqlx::Executor<'e, Database = sqlx::Postgres>;
+
+    async fn update<'e, E>(&self, pool: E) -> eyre::Result<Option<Self>>
+    where
+        E: sqlx::Executor<'e, Database = sqlx::Postgres>;
+
+    async fn upsert<'e, E>(&self, pool: E) -> eyre::Result<Self>
+    where
+        E: sqlx::Executor<'e, Database = sqlx::Postgres>;
+}
+
+#[derive(Default, Debug, sqlx::FromRow, sqlxinsert::PgInsert)]
+struct Car {
+    pub id: i32,
+    pub name: String,
+    pub color: Option<String>,
+}
+
+#[derive(Default, Debug, sqlx::FromRow, sqlxinsert::PgInsert)]
+#[sqlxinsert(
+    table = "cars",
+    update(by = "name,id,color", skip = "id"),
+    conflict = "name, color"
+)]
+struct CreateCar {
+    pub id: Option<i32>,
+    pub name: String,
+    pub color: Option<String>,
+    pub wheels: i16,
+}
+
+#[derive(Default, Debug, sqlx::FromRow, sqlxinsert::PgInsert)]
+#[sqlxinsert(schema = "billing", insert(skip = "created_at, id"), update(by = "id"))]
+struct Invoice {
+    pub id: i32,
+    pub amount: i32,
+    pub created_at: i64,
+}
+
+#[derive(Default, Debug, sqlx::FromRow, sqlxinsert::PgInsert)]
+#[sqlxinsert(table = "events", update(by = "id"), returning = "id, created_at")]
+struct Event {
+    pub id: i32,
+    pub name: String,
+    pub created_at: i64,
+}
+
+#[derive(Default, Debug, sqlx::FromRow, sqlxinsert::PgInsert)]
+#[sqlxinsert(
+    table = "hosts",
+    update(by = "address", skip = "id"),
+    conflict = "address"
+)]
+struct Host {
+    pub id: i32,
+    #[sqlxinsert(cast = "inet")]
+    pub address: String,
+    #[sqlxinsert(insert_expr = "lower($)", update_expr = "lower(trim($))")]
+    pub email: String,
+    #[sqlxinsert(cast = "jsonb", default_if_none)]
+    pub tags: Option<String>,
+}
+
+#[derive(Default, Debug, sqlx::FromRow, sqlxinsert::PgFlatten)]
+struct Address {
+    pub street: String,
+    #[sqlxinsert(insert_expr = "upper($)")]
+    pub city: String,
+}
+
+#[derive(Default, Debug, sqlxinsert::PgInsert)]
+#[sqlxinsert(table = "customers", conflict = "name")]
+struct Customer {
+    pub id: i32,
+    pub name: String,
+    #[sqlxinsert(flatten)]
+    pub address: Address,
+    #[sqlxinsert(flatten, prefix = "billing_")]
+    pub billing: Address,
+    #[sqlxinsert(default_if_none)]
+    pub tier: Option<String>,
+}
+
+#[derive(Default, Debug, sqlx::FromRow, sqlxinsert::SqliteInsert)]
+#[sqlxinsert(ddl, table = "cars")]
+struct SqliteCar {
+    pub id: i32,
+    pub name: String,
+}
+
+// `text` key is not a rowid alias, it stays `not null`
+#[derive(Default, Debug, sqlx::FromRow, sqlxinsert::SqliteInsert)]
+#[sqlxinsert(ddl, table = "categories", update(by = "code"))]
+struct SqliteCategory {
+    pub code: String,
+    pub label: Option<String>,
+}
+
+fn assert_golden(fixture: &str, operations: &[(&str, String)]) {
+    let actual = operations
+        .iter()
+        .map(|(op, sql)| format!("-- {}\n{};\n", op, sql))
+        .collect::<Vec<_>>()
+        .join("\n");
+    common::assert_golden(&format!("{}.sql", fixture), &actual);
+}
+
+#[test]
+fn golden_car() {
+    let table = Car::TABLE;
+    assert_golden(
+        "car",
+        &[
+            ("insert", Car::insert_sql(&table)),
+            ("upsert", Car::upsert_sql(&table)),
+            ("update", Car::update_sql(&table)),
+            ("delete", Car::delete_sql(&table)),
+        ],
+    );
+}
+
+#[test]
+fn golden_create_car() {
+    let table = CreateCar::TABLE;
+    assert_golden(
+        "create_car",
+        &[
+            ("insert", CreateCar::insert_sql(&table)),
+            ("upsert", CreateCar::upsert_sql(&table)),
+            ("update", CreateCar::update_sql(&table)),
+            ("delete", CreateCar::delete_sql(&table)),
+        ],
+    );
+}
+
+#[test]
+fn golden_invoice() {
+    let table = Invoice::TABLE;
+    assert_golden(
+        "invoice",
+        &[
+            ("insert", Invoice::insert_sql(&table)),
+            ("upsert", Invoice::upsert_sql(&table)),
+            ("update", Invoice::update_sql(&table)),
+            ("delete", Invoice::delete_sql(&table)),
+        ],
+    );
+}
+
+#[test]
+fn golden_event() {
+    let table = Event::TABLE;
+    assert_golden(
+        "event",
+        &[
+            ("insert", Event::insert_sql(&table)),
+            ("upsert", Event::upsert_sql(&table)),
+            ("update", Event::update_sql(&table)),
+            ("delete", Event::delete_sql(&table)),
+            (
+                "insert_returning_key",
+                Event::insert_sql_returning(&table, "\"id\""),
+            ),
+            ("execute_insert", Event::insert_sql_returning(&table, "")),
+            ("execute_upsert", Event::upsert_sql_returning(&table, "")),
+            ("execute_update", Event::update_sql_returning(&table, "")),
+        ],
+    );
+}
+
+#[test]
+fn golden_host() {
+    let table = Host::TABLE;
+    assert_golden(
+        "host",
+        &[
+            ("insert", Host::insert_sql(&table)),
+            (
+                "insert default tags",
+                Host::insert_sql_defaults(&table, 1, "*"),
+            ),
+            ("upsert", Host::upsert_sql(&table)),
+            ("update", Host::update_sql(&table)),
+            ("delete", Host::delete_sql(&table)),
+        ],
+    );
+}
+
+#[test]
+fn golden_customer() {
+    let table = Customer::TABLE;
+    assert_golden(
+        "customer",
+        &[
+            ("insert", Customer::insert_sql(&table)),
+            (
+                "insert default tier",
+                Customer::insert_sql_defaults(&table, 1, "*"),
+            ),
+            ("upsert", Customer::upsert_sql(&table)),
+            ("update", Customer::update_sql(&table)),
+            ("delete", Customer::delete_sql(&table)),
+        ],
+    );
+}
+
+#[test]
+fn golden_sqlite_car() {
+    let table = sqlxinsert::TableRef::new("cars").unwrap();
+    assert_golden(
+        "sqlite_car",
+        &[
+            ("create", SqliteCar::CREATE_TABLE_SQL.to_string()),
+            ("insert", SqliteCar::default().insert_query(&table)),
+        ],
+    );
+}
+
+#[test]
+fn golden_sqlite_category() {
+    let table = sqlxinsert::TableRef::new("categories").unwrap();
+    assert_golden(
+        "sqlite_category",
+        &[
+            ("create", SqliteCategory::CREATE_TABLE_SQL.to_string()),
+            ("insert", SqliteCategory::default().insert_query(&table)),
+        ],
+    );
+}
@@ -2,7 +2,7 @@
 //extern crate gmacro;
 
 // #[derive(Default, Debug, sqlx::FromRow)]
-/*#[derive(Default, Debug, sqlx::FromRow, sqlxinsert::SqliteInsert)]
+#[derive(Default, Debug, sqlx::FromRow, sqlxinsert::SqliteInsert)]
 struct Car {
     pub car_id: i32,
     pub car_name: String,
@@ -44,4 +44,87 @@ async fn test_macro_sqlite_insert() {
     assert_eq!(rows.len(), 1);
     assert_eq!(rows[0].car_name, "Skoda");
 }
-*/
\ No newline at end of file
+
+#[derive(Default, Debug, sqlx::FromRow, sqlxinsert::SqliteInsert)]
+struct Order {
+    pub order: i32,
+    pub group: String,
+    pub r#type: String,
+}
+
+#[tokio::test]
+async fn test_macro_sqlite_insert_quoted() {
+    let order = Order {
+        order: 1,
+        group: "admins".to_string(),
+        r#type: "fast".to_string(),
+    };
+
+    let pool = sqlx::sqlite::SqlitePoolOptions::new()
+        .connect("sqlite::memory:")
+        .await
+        .expect("Not possible to create pool");
+
+    let create_table = r#"create table "order" (
+        "order" INTEGER PRIMARY KEY,
+        "group" TEXT NOT NULL,
+        "type" TEXT NOT NULL
+    )"#;
+    sqlx::query(create_table)
+        .execute(&pool)
+        .await
+        .expect("Not possible to execute");
+
+    let table = sqlxinsert::TableRef::new("order").unwrap();
+    assert_eq!(
+        order.insert_query(&table),
+        r#"insert into "order" ( "order", "group", "type" ) values ( $1,$2,$3 )"#
+    );
+
+    order.insert_raw(&pool, "order").await.unwrap();
+    assert!(order
+        .insert_raw(&pool, "order\" ( x ) values ( 1 ); --")
+        .await
+        .is_err());
+
+    let rows = sqlx::query_as::<_, Order>(r#"SELECT * FROM "order""#)
+        .fetch_all(&pool)
+        .await
+        .expect("Not possible to fetch");
+
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].group, "admins");
+    assert_eq!(rows[0].r#type, "fast");
+}
+
+#[derive(Default, Debug, sqlx::FromRow, sqlxinsert::SqliteInsert)]
+#[sqlxinsert(ddl, table = "ddl_cars", update(by = "car_id"), conflict = "car_name")]
+struct DdlCar {
+    pub car_id: i32,
+    pub car_name: String,
+    pub color: Option<String>,
+    pub price: f64,
+}
+
+#[tokio::test]
+async fn test_sqlite_create_table() {
+    assert_eq!(
+        DdlCar::CREATE_TABLE_SQL,
+        r#"create table "ddl_cars" ( "car_id" integer primary key, "car_name" text not null, "color" text, "price" real not null, unique ("car_name") )"#
+    );
+
+    let pool = sqlx::sqlite::SqlitePoolOptions::new()
+        .connect("sqlite::memory:")
+        .await
+        .expect("Not possible to create pool");
+    DdlCar::create_table(&pool).await.unwrap();
+
+    let car = DdlCar {
+        car_id: 7,
+        car_name: "Skoda".to_string(),
+        color: None,
+        price: 1.5,
+    };
+    car.insert_raw(&pool, "ddl_cars").await.unwrap();
+    assert!(car.insert_raw(&pool, "ddl_cars").await.is_err());
+}